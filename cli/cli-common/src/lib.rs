@@ -20,6 +20,7 @@ use solana_sdk::{
     signature::{write_keypair_file, Signer},
 };
 
+pub mod records;
 pub mod rpc_client_helpers;
 pub mod rpc_marinade;
 
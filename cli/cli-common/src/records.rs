@@ -0,0 +1,1023 @@
+//! Records of the CSV artifacts passed between the steps of the pipeline.
+//!
+//! Every artifact starts with a schema header, e.g. `#schema: post-processed-scores v3`,
+//! followed by the CSV with the column names. The reader checks the header against the
+//! versions of the artifact it understands, so a step reading an artifact written with other
+//! columns fails with the versions involved instead of misreading it.
+//! Version 1 is the layout of the files written before the schema header.
+//!
+//! The artifacts can also be exported as JSON Lines or Parquet for the analytics, see
+//! [ArtifactFormat]. Those exports are not read back by the pipeline.
+
+use anyhow::{anyhow, bail, Context};
+use log::warn;
+use parquet::{
+    basic::{Repetition, Type as PhysicalType},
+    column::writer::ColumnWriter,
+    file::{
+        metadata::KeyValue,
+        properties::WriterProperties,
+        writer::{FileWriter, RowGroupWriter, SerializedFileWriter},
+    },
+    record::RecordWriter,
+    schema::types::Type as ParquetType,
+};
+use parquet_derive::ParquetRecordWriter;
+use serde::{
+    de::{self, DeserializeOwned, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::{
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    marker::PhantomData,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
+
+const SCHEMA_HEADER_PREFIX: &str = "#schema:";
+
+/// Record of a CSV artifact, a row of the file
+pub trait ArtifactRecord: Serialize + DeserializeOwned {
+    /// Name of the artifact in the schema header
+    const ARTIFACT: &'static str;
+    /// Version of the columns, bumped on every column added, removed or changed
+    const SCHEMA_VERSION: u32;
+    /// Oldest version this record can still be read from, the columns added since have a
+    /// default value
+    const MIN_SCHEMA_VERSION: u32;
+}
+
+/// First line of an artifact: `#schema: <artifact> v<version>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaHeader {
+    pub artifact: String,
+    pub version: u32,
+}
+
+impl SchemaHeader {
+    pub fn of<R: ArtifactRecord>() -> Self {
+        Self {
+            artifact: R::ARTIFACT.to_string(),
+            version: R::SCHEMA_VERSION,
+        }
+    }
+
+    /// `None` if the line is not a schema header
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut words = line
+            .trim()
+            .strip_prefix(SCHEMA_HEADER_PREFIX)?
+            .split_whitespace();
+        let artifact = words.next()?.to_string();
+        let version = words.next()?.strip_prefix('v')?.parse().ok()?;
+        if words.next().is_some() {
+            return None;
+        }
+        Some(Self { artifact, version })
+    }
+
+    /// Checks an artifact with this header can be read into `R`
+    pub fn check<R: ArtifactRecord>(&self) -> anyhow::Result<()> {
+        if self.artifact != R::ARTIFACT {
+            bail!("Expected a {} file, found {}", R::ARTIFACT, self.artifact);
+        }
+        if self.version < R::MIN_SCHEMA_VERSION {
+            bail!(
+                "{} schema v{} is no longer supported, the oldest supported is v{}: regenerate the file",
+                R::ARTIFACT,
+                self.version,
+                R::MIN_SCHEMA_VERSION
+            );
+        }
+        if self.version > R::SCHEMA_VERSION {
+            // the columns are read by name, the columns added since are ignored
+            warn!(
+                "{} schema v{} is newer than v{}, the new columns are ignored",
+                R::ARTIFACT,
+                self.version,
+                R::SCHEMA_VERSION
+            );
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SchemaHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} v{}",
+            SCHEMA_HEADER_PREFIX, self.artifact, self.version
+        )
+    }
+}
+
+/// Names of the fields of the struct `R`, in declaration order, i.e. the CSV columns
+pub fn columns<'de, R: Deserialize<'de>>() -> &'static [&'static str] {
+    let mut collector = StructFields(&[]);
+    // the collector fails once the fields are known
+    let _ = R::deserialize(&mut collector);
+    collector.0
+}
+
+struct StructFields(&'static [&'static str]);
+
+impl<'de, 'a> Deserializer<'de> for &'a mut StructFields {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0 = fields;
+        Err(de::Error::custom("fields collected"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+/// Writes an artifact, the schema header, the column names and then a line per record
+pub struct ArtifactWriter<R: ArtifactRecord> {
+    writer: csv::Writer<File>,
+    _record: PhantomData<R>,
+}
+
+impl<R: ArtifactRecord> ArtifactWriter<R> {
+    pub fn create<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        Self::with_extra_columns(path, &[])
+    }
+
+    /// `extra_columns` follow the columns of the record, e.g. the score components of a
+    /// scoring model, see [Self::write_with_extra]
+    pub fn with_extra_columns<P: AsRef<Path>>(
+        path: P,
+        extra_columns: &[&str],
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let mut file =
+            File::create(path).with_context(|| format!("Unable to create {}", path.display()))?;
+        writeln!(file, "{}", SchemaHeader::of::<R>())?;
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(file);
+        writer.write_record(columns::<R>().iter().chain(extra_columns))?;
+        Ok(Self {
+            writer,
+            _record: PhantomData,
+        })
+    }
+
+    pub fn write(&mut self, record: &R) -> anyhow::Result<()> {
+        self.writer.serialize(record)?;
+        Ok(())
+    }
+
+    /// Writes the record followed by the values of the extra columns
+    pub fn write_with_extra<E: Serialize>(
+        &mut self,
+        record: &R,
+        extra: &[E],
+    ) -> anyhow::Result<()> {
+        self.writer.serialize((record, extra))?;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> anyhow::Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Writes `records` to the artifact at `path`
+pub fn write_artifact<R: ArtifactRecord, P: AsRef<Path>>(
+    path: P,
+    records: &[R],
+) -> anyhow::Result<()> {
+    let mut writer = ArtifactWriter::create(path)?;
+    for record in records {
+        writer.write(record)?;
+    }
+    writer.finish()
+}
+
+/// Reads the artifact at `path`, checking its schema header. A file without schema header is
+/// read as version 1.
+pub fn read_artifact<R: ArtifactRecord, P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<R>> {
+    let path = path.as_ref();
+    let file = File::open(path).with_context(|| format!("Unable to open {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let header = if reader
+        .fill_buf()?
+        .starts_with(SCHEMA_HEADER_PREFIX.as_bytes())
+    {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        SchemaHeader::parse(&line).with_context(|| {
+            format!(
+                "Invalid schema header in {}: {}",
+                path.display(),
+                line.trim()
+            )
+        })?
+    } else {
+        SchemaHeader {
+            artifact: R::ARTIFACT.to_string(),
+            version: 1,
+        }
+    };
+    header
+        .check::<R>()
+        .with_context(|| format!("Unable to read {}", path.display()))?;
+
+    let mut reader = csv::Reader::from_reader(reader);
+    let file_columns = reader.headers()?.clone();
+    reader
+        .deserialize()
+        .collect::<Result<Vec<R>, _>>()
+        .with_context(|| {
+            let missing: Vec<_> = columns::<R>()
+                .iter()
+                .filter(|column| !file_columns.iter().any(|name| name == **column))
+                .collect();
+            format!(
+                "Invalid {} schema v{} in {}, missing columns: {:?}",
+                header.artifact,
+                header.version,
+                path.display(),
+                missing
+            )
+        })
+}
+
+/// Format an artifact is exported in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactFormat {
+    /// CSV with the schema header, the format read by the pipeline
+    Csv,
+    /// a JSON object per line
+    Jsonl,
+    /// Parquet with a typed column per field, the schema header in the key-value metadata
+    Parquet,
+}
+
+impl ArtifactFormat {
+    pub const NAMES: &'static [&'static str] = &["csv", "jsonl", "parquet"];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArtifactFormat::Csv => "csv",
+            ArtifactFormat::Jsonl => "jsonl",
+            ArtifactFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl Default for ArtifactFormat {
+    fn default() -> Self {
+        ArtifactFormat::Csv
+    }
+}
+
+impl FromStr for ArtifactFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(ArtifactFormat::Csv),
+            "jsonl" => Ok(ArtifactFormat::Jsonl),
+            "parquet" => Ok(ArtifactFormat::Parquet),
+            _ => Err(anyhow!(
+                "Unknown format {}, expected one of {}",
+                s,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ArtifactFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// Writes `records` to the artifact at `path` in `format`
+pub fn export_artifact<R, P>(path: P, records: &[R], format: ArtifactFormat) -> anyhow::Result<()>
+where
+    R: ArtifactRecord,
+    for<'a> &'a [R]: RecordWriter<R>,
+    P: AsRef<Path>,
+{
+    match format {
+        ArtifactFormat::Csv => write_artifact(path, records),
+        ArtifactFormat::Jsonl => write_jsonl(path, records),
+        ArtifactFormat::Parquet => write_parquet(path, records),
+    }
+}
+
+/// Writes a JSON object per record and per line
+pub fn write_jsonl<T, I, P>(path: P, records: I) -> anyhow::Result<()>
+where
+    T: Serialize,
+    I: IntoIterator<Item = T>,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let file =
+        File::create(path).with_context(|| format!("Unable to create {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    for record in records {
+        serde_json::to_writer(&mut writer, &record)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the records in a single row group, the schema header of the artifact in the
+/// `schema` key of the file metadata
+pub fn write_parquet<R, P>(path: P, records: &[R]) -> anyhow::Result<()>
+where
+    R: ArtifactRecord,
+    for<'a> &'a [R]: RecordWriter<R>,
+    P: AsRef<Path>,
+{
+    write_parquet_with_extra(path, records, &[], &[])
+}
+
+/// Writes the records followed by `extra_columns`, nullable integer columns only known at run
+/// time, e.g. the score components of a scoring model. `extra` has the values of each record,
+/// it is empty without extra columns.
+pub fn write_parquet_with_extra<R, P>(
+    path: P,
+    records: &[R],
+    extra_columns: &[&str],
+    extra: &[Vec<Option<i64>>],
+) -> anyhow::Result<()>
+where
+    R: ArtifactRecord,
+    for<'a> &'a [R]: RecordWriter<R>,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    if !extra_columns.is_empty()
+        && (extra.len() != records.len()
+            || extra
+                .iter()
+                .any(|values| values.len() != extra_columns.len()))
+    {
+        bail!(
+            "Expected {} values of {:?} for each of the {} records of {}",
+            extra_columns.len(),
+            extra_columns,
+            records.len(),
+            path.display()
+        );
+    }
+    let mut fields = records.schema()?.get_fields().to_vec();
+    for column in extra_columns {
+        fields.push(Arc::new(
+            ParquetType::primitive_type_builder(column, PhysicalType::INT64)
+                .with_repetition(Repetition::OPTIONAL)
+                .build()?,
+        ));
+    }
+    let schema = ParquetType::group_type_builder("rust_schema")
+        .with_fields(&mut fields)
+        .build()?;
+
+    let file =
+        File::create(path).with_context(|| format!("Unable to create {}", path.display()))?;
+    let properties = WriterProperties::builder()
+        .set_key_value_metadata(Some(vec![KeyValue::new(
+            "schema".to_string(),
+            SchemaHeader::of::<R>().to_string(),
+        )]))
+        .build();
+    let mut writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(properties))?;
+    let mut row_group = writer.next_row_group()?;
+    records.write_to_row_group(&mut row_group)?;
+    for (index, column) in extra_columns.iter().enumerate() {
+        let values: Vec<i64> = extra.iter().filter_map(|values| values[index]).collect();
+        let definition_levels: Vec<i16> = extra
+            .iter()
+            .map(|values| values[index].is_some() as i16)
+            .collect();
+        match row_group.next_column()? {
+            Some(ColumnWriter::Int64ColumnWriter(mut column_writer)) => {
+                column_writer.write_batch(&values, Some(&definition_levels), None)?;
+                row_group.close_column(ColumnWriter::Int64ColumnWriter(column_writer))?;
+            }
+            _ => bail!(
+                "Unable to write the column {} of {}",
+                column,
+                path.display()
+            ),
+        }
+    }
+    writer.close_row_group(row_group)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// A line of `{cluster}-validator-detail.csv`, written by `score-past-epoch` and imported
+/// into the scores history. The points of each component of the scoring model follow, one
+/// column per component.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ValidatorDetailRecord {
+    pub epoch: u64,
+    pub keybase_id: String,
+    pub name: String,
+    pub identity: String,
+    pub vote_address: String,
+    pub score: u64,
+    pub average_position: f64,
+    pub commission: u8,
+    /// SOL, not lamports
+    pub active_stake: f64,
+    /// the same active stake, exact
+    #[serde(default)]
+    pub active_stake_lamports: u64,
+    pub epoch_credits: u64,
+    pub data_center_concentration: f64,
+    pub data_center_asn: u64,
+    pub data_center_location: String,
+    #[serde(default)]
+    pub asn_concentration: f64,
+    #[serde(default)]
+    pub country_concentration: f64,
+    #[serde(default)]
+    pub city_concentration: f64,
+    #[serde(default)]
+    pub hosting_provider_concentration: f64,
+    pub can_halt_the_network_group: bool,
+    pub stake_state: String,
+    pub stake_state_reason: String,
+    pub www_url: String,
+    pub version: String,
+    // the validators.app sub-scores, empty when not reported
+    pub root_distance_score: Option<i64>,
+    pub vote_distance_score: Option<i64>,
+    pub skipped_slot_score: Option<i64>,
+    pub published_information_score: Option<i64>,
+    pub security_report_score: Option<i64>,
+    pub software_version_score: Option<i64>,
+    /// milliseconds
+    pub ping_time: Option<f64>,
+    #[serde(default)]
+    pub score_model: String,
+    #[serde(default)]
+    pub score_zeroed_reason: String,
+}
+
+impl ArtifactRecord for ValidatorDetailRecord {
+    const ARTIFACT: &'static str = "validator-detail";
+    const SCHEMA_VERSION: u32 = 3;
+    const MIN_SCHEMA_VERSION: u32 = 1;
+}
+
+/// A row of the validator detail exported as JSON Lines or Parquet: the columns of
+/// [ValidatorDetailRecord] without the active stake in SOL, rounded, the exact one in lamports
+/// is kept
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, ParquetRecordWriter)]
+pub struct ValidatorDetailExportRecord {
+    pub epoch: u64,
+    pub keybase_id: String,
+    pub name: String,
+    pub identity: String,
+    pub vote_address: String,
+    pub score: u64,
+    pub average_position: f64,
+    pub commission: u8,
+    /// the active stake in lamports
+    pub active_stake_lamports: u64,
+    pub epoch_credits: u64,
+    pub data_center_concentration: f64,
+    pub data_center_asn: u64,
+    pub data_center_location: String,
+    pub asn_concentration: f64,
+    pub country_concentration: f64,
+    pub city_concentration: f64,
+    pub hosting_provider_concentration: f64,
+    pub can_halt_the_network_group: bool,
+    pub stake_state: String,
+    pub stake_state_reason: String,
+    pub www_url: String,
+    pub version: String,
+    // the validators.app sub-scores, empty when not reported
+    pub root_distance_score: Option<i64>,
+    pub vote_distance_score: Option<i64>,
+    pub skipped_slot_score: Option<i64>,
+    pub published_information_score: Option<i64>,
+    pub security_report_score: Option<i64>,
+    pub software_version_score: Option<i64>,
+    /// milliseconds
+    pub ping_time: Option<f64>,
+    pub score_model: String,
+    pub score_zeroed_reason: String,
+}
+
+impl ArtifactRecord for ValidatorDetailExportRecord {
+    const ARTIFACT: &'static str = "validator-detail-export";
+    const SCHEMA_VERSION: u32 = 1;
+    const MIN_SCHEMA_VERSION: u32 = 1;
+}
+
+impl From<&ValidatorDetailRecord> for ValidatorDetailExportRecord {
+    fn from(record: &ValidatorDetailRecord) -> Self {
+        Self {
+            epoch: record.epoch,
+            keybase_id: record.keybase_id.clone(),
+            name: record.name.clone(),
+            identity: record.identity.clone(),
+            vote_address: record.vote_address.clone(),
+            score: record.score,
+            average_position: record.average_position,
+            commission: record.commission,
+            active_stake_lamports: record.active_stake_lamports,
+            epoch_credits: record.epoch_credits,
+            data_center_concentration: record.data_center_concentration,
+            data_center_asn: record.data_center_asn,
+            data_center_location: record.data_center_location.clone(),
+            asn_concentration: record.asn_concentration,
+            country_concentration: record.country_concentration,
+            city_concentration: record.city_concentration,
+            hosting_provider_concentration: record.hosting_provider_concentration,
+            can_halt_the_network_group: record.can_halt_the_network_group,
+            stake_state: record.stake_state.clone(),
+            stake_state_reason: record.stake_state_reason.clone(),
+            www_url: record.www_url.clone(),
+            version: record.version.clone(),
+            root_distance_score: record.root_distance_score,
+            vote_distance_score: record.vote_distance_score,
+            skipped_slot_score: record.skipped_slot_score,
+            published_information_score: record.published_information_score,
+            security_report_score: record.security_report_score,
+            software_version_score: record.software_version_score,
+            ping_time: record.ping_time,
+            score_model: record.score_model.clone(),
+            score_zeroed_reason: record.score_zeroed_reason.clone(),
+        }
+    }
+}
+
+/// A line of `{cluster}-commission-flipping.csv`, the validators whose commission at reward
+/// time was above the one at scoring time
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ParquetRecordWriter)]
+pub struct CommissionFlippingRecord {
+    pub epoch: u64,
+    pub keybase_id: String,
+    pub name: String,
+    pub identity: String,
+    pub vote_address: String,
+    pub commission: u8,
+    pub reward_commission: u8,
+}
+
+impl ArtifactRecord for CommissionFlippingRecord {
+    const ARTIFACT: &'static str = "commission-flipping";
+    const SCHEMA_VERSION: u32 = 2;
+    const MIN_SCHEMA_VERSION: u32 = 1;
+}
+
+/// A line of `{cluster}-vote-latency.csv`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ParquetRecordWriter)]
+pub struct VoteLatencyRecord {
+    pub epoch: u64,
+    pub identity: String,
+    pub vote_address: String,
+    /// slots
+    pub avg_vote_latency: f64,
+}
+
+impl ArtifactRecord for VoteLatencyRecord {
+    const ARTIFACT: &'static str = "vote-latency";
+    const SCHEMA_VERSION: u32 = 2;
+    const MIN_SCHEMA_VERSION: u32 = 1;
+}
+
+/// A line of `{cluster}-multiple-vote-accounts.csv`, one per vote account of the validators
+/// with multiple vote accounts
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ParquetRecordWriter)]
+pub struct MultipleVoteAccountsRecord {
+    pub epoch: u64,
+    pub keybase_id: String,
+    pub name: String,
+    pub identity: String,
+    pub vote_address: String,
+    /// the vote account the validator was scored with
+    pub scored: bool,
+    /// SOL, not lamports
+    pub active_stake: f64,
+    /// the same active stake, exact
+    #[serde(default)]
+    pub active_stake_lamports: u64,
+    pub commission: u8,
+    pub epoch_credits: u64,
+}
+
+impl ArtifactRecord for MultipleVoteAccountsRecord {
+    const ARTIFACT: &'static str = "multiple-vote-accounts";
+    const SCHEMA_VERSION: u32 = 3;
+    const MIN_SCHEMA_VERSION: u32 = 1;
+}
+
+/// A line of `{cluster}-operator-groups.csv`, a validator run by the same operator as other
+/// validators
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ParquetRecordWriter)]
+pub struct OperatorGroupRecord {
+    pub epoch: u64,
+    pub operator_group: u32,
+    pub keybase_id: String,
+    pub name: String,
+    pub identity: String,
+    pub vote_address: String,
+    /// the links shared by the validators of the group, space separated
+    pub evidence: String,
+}
+
+impl ArtifactRecord for OperatorGroupRecord {
+    const ARTIFACT: &'static str = "operator-groups";
+    const SCHEMA_VERSION: u32 = 2;
+    const MIN_SCHEMA_VERSION: u32 = 1;
+}
+
+/// A line of the `avg.csv` written by `score-history` and consumed by `score-post-process`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ParquetRecordWriter)]
+pub struct AvgRecord {
+    pub rank: u32,
+    pub pct: f64,
+    pub epoch: u64,
+    pub keybase_id: String,
+    pub name: String,
+    pub vote_address: String,
+    pub score: u64,
+    pub average_position: f64,
+    pub epoch_credits: u64,
+    pub commission: u8,
+    pub max_commission: u8,
+    pub data_center_concentration: f64,
+    pub data_center_asn: u64,
+    pub data_center_location: String,
+    pub base_score: f64,
+    pub mult: f64,
+    pub avg_score: f64,
+    pub avg_active_stake: f64,
+    pub identity: String,
+    pub can_halt_the_network_group: bool,
+    pub stake_conc: f64,
+    pub version: String,
+    pub url: String,
+}
+
+impl ArtifactRecord for AvgRecord {
+    const ARTIFACT: &'static str = "avg";
+    const SCHEMA_VERSION: u32 = 2;
+    const MIN_SCHEMA_VERSION: u32 = 1;
+}
+
+/// A line of the `post-processed-scores.csv` written by `score-post-process` and imported
+/// into the post-processed history
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, ParquetRecordWriter)]
+pub struct PostProcessedRecord {
+    pub epoch: u64,
+    pub rank: u32,
+    pub score: u32,
+    pub marinade_score: u32,
+    pub collateral_score: u32,
+    pub collateral_shares: u64,
+    pub vote_score: u32,
+    pub votes_read: u64,
+    pub votes_effective: u64,
+    pub name: String,
+    pub credits_observed: u64,
+    pub vote_address: String,
+    pub commission: u8,
+    pub max_commission: u8,
+    pub average_position: f64,
+    pub data_center_concentration: f64,
+    pub data_center_asn: u64,
+    pub data_center_location: String,
+    pub avg_active_stake: f64,
+    /// percent
+    pub apy: Option<f64>,
+    pub delinquent: bool,
+    pub this_epoch_credits: u64,
+    pub pct: f64,
+    /// SOL
+    pub marinade_staked: f64,
+    /// SOL
+    pub should_have: f64,
+    /// the same Marinade stake, exact
+    #[serde(default)]
+    pub marinade_staked_lamports: u64,
+    /// the same stake target, exact
+    #[serde(default)]
+    pub should_have_lamports: u64,
+    /// 0=healthy, 1=warn (score *= 0.5), 2=unstake, 3=unstake & remove from list
+    pub remove_level: u8,
+    pub remove_level_reason: String,
+    pub under_nakamoto_coefficient: bool,
+    pub keybase_id: String,
+    pub identity: String,
+    pub stake_concentration: f64,
+    pub base_score: u64,
+    pub url: String,
+    pub version: String,
+    pub avg_vote_latency: Option<f64>,
+    pub operator_group: Option<u32>,
+}
+
+impl ArtifactRecord for PostProcessedRecord {
+    const ARTIFACT: &'static str = "post-processed-scores";
+    const SCHEMA_VERSION: u32 = 3;
+    const MIN_SCHEMA_VERSION: u32 = 1;
+}
+
+/// A stake operation of the plan written by `score-post-process plan-rebalance`, in the order
+/// the operations are to be executed
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, ParquetRecordWriter)]
+pub struct RebalanceOperationRecord {
+    pub epoch: u64,
+    /// execution order, from 1
+    pub order: u32,
+    /// `merge`, `unstake` or `stake`
+    pub operation: String,
+    pub vote_address: String,
+    pub name: String,
+    /// stake account deactivated, split or merged, empty for a stake from the reserve
+    pub stake_account: String,
+    /// stake account a merged stake account is merged into, empty otherwise
+    pub destination_stake_account: String,
+    /// lamports moved, the balance of the merged stake account for a merge
+    pub lamports: u64,
+    /// active Marinade stake of the validator before the plan
+    pub marinade_staked_lamports: u64,
+    /// Marinade stake target of the validator, `should_have` of the post-processed scores
+    pub should_have_lamports: u64,
+    pub reason: String,
+}
+
+impl ArtifactRecord for RebalanceOperationRecord {
+    const ARTIFACT: &'static str = "rebalance-plan";
+    const SCHEMA_VERSION: u32 = 1;
+    const MIN_SCHEMA_VERSION: u32 = 1;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn vote_latency(vote_address: &str, avg_vote_latency: f64) -> VoteLatencyRecord {
+        VoteLatencyRecord {
+            epoch: 300,
+            identity: format!("identity-{}", vote_address),
+            vote_address: vote_address.into(),
+            avg_vote_latency,
+        }
+    }
+
+    fn temp_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("cli-common-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_columns() {
+        assert_eq!(
+            columns::<VoteLatencyRecord>(),
+            &["epoch", "identity", "vote_address", "avg_vote_latency"]
+        );
+    }
+
+    #[test]
+    fn test_parse_schema_header() {
+        let header = SchemaHeader::parse("#schema: vote-latency v2\n").unwrap();
+        assert_eq!(header, SchemaHeader::of::<VoteLatencyRecord>());
+        assert_eq!(SchemaHeader::parse(&header.to_string()), Some(header));
+        assert_eq!(SchemaHeader::parse("#schema: vote-latency 2"), None);
+        assert_eq!(SchemaHeader::parse("epoch,identity"), None);
+    }
+
+    #[test]
+    fn test_write_read_artifact() {
+        let path = temp_file("vote-latency.csv");
+        let records = vec![vote_latency("a", 1.5), vote_latency("b", 12.0)];
+        write_artifact(&path, &records).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with(
+            "#schema: vote-latency v2\nepoch,identity,vote_address,avg_vote_latency\n"
+        ));
+        assert_eq!(
+            read_artifact::<VoteLatencyRecord, _>(&path).unwrap(),
+            records
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_export_jsonl() {
+        assert_eq!(
+            "jsonl".parse::<ArtifactFormat>().unwrap(),
+            ArtifactFormat::Jsonl
+        );
+        assert!("xlsx".parse::<ArtifactFormat>().is_err());
+
+        let path = temp_file("vote-latency.jsonl");
+        let records = vec![vote_latency("a", 1.5), vote_latency("b", 12.0)];
+        export_artifact(&path, &records, ArtifactFormat::Jsonl).unwrap();
+        let lines: Vec<VoteLatencyRecord> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines, records);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_export_parquet() {
+        use parquet::{
+            file::reader::{FileReader, SerializedFileReader},
+            record::Field,
+        };
+        use std::collections::HashMap;
+
+        let path = temp_file("post-processed-scores.parquet");
+        let records = vec![
+            PostProcessedRecord {
+                epoch: 300,
+                rank: 1,
+                name: "validator-a".into(),
+                vote_address: "a".into(),
+                commission: 5,
+                apy: Some(7.25),
+                marinade_staked: 1.5,
+                marinade_staked_lamports: 1_500_000_000,
+                should_have_lamports: 2_000_000_001,
+                operator_group: Some(3),
+                ..PostProcessedRecord::default()
+            },
+            PostProcessedRecord {
+                epoch: 300,
+                rank: 2,
+                name: "validator-b".into(),
+                vote_address: "b".into(),
+                remove_level: 2,
+                remove_level_reason: "Blacklisted".into(),
+                ..PostProcessedRecord::default()
+            },
+        ];
+        export_artifact(&path, &records, ArtifactFormat::Parquet).unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let file_metadata = reader.metadata().file_metadata();
+        assert_eq!(file_metadata.num_rows(), 2);
+        let schema = file_metadata
+            .key_value_metadata()
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|key_value| key_value.key == "schema")
+            .and_then(|key_value| key_value.value.clone());
+        assert_eq!(
+            schema,
+            Some(SchemaHeader::of::<PostProcessedRecord>().to_string())
+        );
+
+        let rows: Vec<HashMap<String, Field>> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| {
+                row.get_column_iter()
+                    .map(|(name, field)| (name.clone(), field.clone()))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(rows.len(), 2);
+        for row in &rows {
+            assert_eq!(row.len(), columns::<PostProcessedRecord>().len());
+        }
+        assert_eq!(rows[0]["vote_address"], Field::Str("a".into()));
+        assert_eq!(rows[0]["commission"], Field::UByte(5));
+        assert_eq!(rows[0]["apy"], Field::Double(7.25));
+        assert_eq!(rows[0]["marinade_staked"], Field::Double(1.5));
+        assert_eq!(
+            rows[0]["marinade_staked_lamports"],
+            Field::ULong(1_500_000_000)
+        );
+        assert_eq!(rows[0]["should_have_lamports"], Field::ULong(2_000_000_001));
+        assert_eq!(rows[0]["operator_group"], Field::UInt(3));
+        assert_eq!(rows[1]["rank"], Field::UInt(2));
+        assert_eq!(rows[1]["remove_level"], Field::UByte(2));
+        assert_eq!(
+            rows[1]["remove_level_reason"],
+            Field::Str("Blacklisted".into())
+        );
+        assert_eq!(rows[1]["apy"], Field::Null);
+        assert_eq!(rows[1]["operator_group"], Field::Null);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_export_parquet_with_extra_columns() {
+        use parquet::{
+            file::reader::{FileReader, SerializedFileReader},
+            record::Field,
+        };
+
+        let path = temp_file("validator-detail.parquet");
+        let record = ValidatorDetailRecord {
+            epoch: 300,
+            vote_address: "a".into(),
+            score: 1_500,
+            active_stake: 1.5,
+            active_stake_lamports: 1_500_000_001,
+            ..ValidatorDetailRecord::default()
+        };
+        let records = vec![ValidatorDetailExportRecord::from(&record); 2];
+        write_parquet_with_extra(
+            &path,
+            &records,
+            &["base", "skip_rate"],
+            &[vec![Some(1_000), Some(500)], vec![Some(-20), None]],
+        )
+        .unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let rows: Vec<Vec<(String, Field)>> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| {
+                row.get_column_iter()
+                    .map(|(name, field)| (name.clone(), field.clone()))
+                    .collect()
+            })
+            .collect();
+        let names: Vec<&str> = rows[0].iter().map(|(name, _)| name.as_str()).collect();
+        assert!(!names.contains(&"active_stake"));
+        assert_eq!(names.len(), columns::<ValidatorDetailRecord>().len() + 1);
+        assert_eq!(&names[names.len() - 2..], &["base", "skip_rate"]);
+        let field = |row: usize, name: &str| {
+            rows[row]
+                .iter()
+                .find(|(column, _)| column == name)
+                .map(|(_, field)| field.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            field(0, "active_stake_lamports"),
+            Field::ULong(1_500_000_001)
+        );
+        assert_eq!(field(0, "base"), Field::Long(1_000));
+        assert_eq!(field(0, "skip_rate"), Field::Long(500));
+        assert_eq!(field(1, "base"), Field::Long(-20));
+        assert_eq!(field(1, "skip_rate"), Field::Null);
+
+        // the values of every extra column are required
+        assert!(write_parquet_with_extra(&path, &records, &["base"], &[vec![Some(1)]]).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_read_artifact_without_header() {
+        let path = temp_file("legacy-vote-latency.csv");
+        std::fs::write(
+            &path,
+            "epoch,identity,vote_address,avg_vote_latency\n300,\"identity-a\",\"a\",1.5000",
+        )
+        .unwrap();
+        assert_eq!(
+            read_artifact::<VoteLatencyRecord, _>(&path).unwrap(),
+            vec![vote_latency("a", 1.5)]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_read_artifact_incompatible() {
+        let path = temp_file("other-artifact.csv");
+        std::fs::write(&path, "#schema: operator-groups v2\nepoch\n300").unwrap();
+        assert!(read_artifact::<VoteLatencyRecord, _>(&path).is_err());
+        std::fs::write(
+            &path,
+            "#schema: vote-latency v2\nepoch,identity,vote_address\n300,identity-a,a",
+        )
+        .unwrap();
+        let error = read_artifact::<VoteLatencyRecord, _>(&path).unwrap_err();
+        assert!(format!("{:#}", error).contains("avg_vote_latency"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,80 @@
+#![cfg_attr(not(debug_assertions), deny(warnings))]
+
+use anyhow::bail;
+use cli_common::{ExpandedPath, InputPubkey};
+use log::{debug, error, info};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::{str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+pub mod run_epoch;
+pub mod steps;
+
+use run_epoch::RunEpochOptions;
+
+#[derive(Debug, StructOpt)]
+pub struct Common {
+    #[structopt(short = "c", default_value = "~/.config/solana/cli/config.yml")]
+    config_file: ExpandedPath,
+
+    #[structopt(
+        short = "i",
+        env = "MARINADE_INSTANCE",
+        default_value = "auto" //select default instance based on cluster
+    )]
+    instance: InputPubkey,
+}
+
+#[derive(Debug, StructOpt)]
+struct Params {
+    #[structopt(flatten)]
+    common: Common,
+
+    #[structopt(subcommand)]
+    command: DelegationStrategyCommand,
+}
+
+#[derive(Debug, StructOpt)]
+enum DelegationStrategyCommand {
+    /// Score the past epoch and post-process the scores, resumable step by step
+    RunEpoch(RunEpochOptions),
+}
+
+fn main() -> anyhow::Result<()> {
+    let params = Params::from_args();
+
+    solana_logger::setup_with("info");
+
+    let cli_config = match solana_cli_config::Config::load(&params.common.config_file.to_string()) {
+        Ok(cli_config) => cli_config,
+        Err(err) => {
+            error!(
+                "Solana CLI config {} reading error: {}",
+                params.common.config_file.to_string(),
+                err
+            );
+            bail!(
+                "Solana CLI config {} reading error: {}",
+                params.common.config_file.to_string(),
+                err
+            );
+        }
+    };
+    info!(
+        "RPC URL: {}, commitment: {}",
+        cli_config.json_rpc_url, &cli_config.commitment
+    );
+    debug!("Solana config: {:?}", cli_config);
+
+    let client = Arc::new(RpcClient::new_with_commitment(
+        cli_config.json_rpc_url.clone(),
+        CommitmentConfig::from_str(&cli_config.commitment).unwrap(),
+    ));
+
+    match params.command {
+        DelegationStrategyCommand::RunEpoch(options) => {
+            options.process(params.common, client, cli_config.json_rpc_url)
+        }
+    }
+}
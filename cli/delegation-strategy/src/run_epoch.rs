@@ -0,0 +1,537 @@
+use crate::steps::{RunState, Step};
+use anyhow::{anyhow, bail, Context};
+use cli_common::{Cluster, InputPubkey};
+use log::info;
+use score_history::{
+    create_avg, db::ScoresDb, import_post_processed, import_validator_detail, write_avg_file,
+};
+use score_post_process::{
+    cluster_validators::ClusterValidators, process_scores::ProcessScoresOptions,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::clock::Epoch;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub struct RunEpochOptions {
+    #[structopt(
+        long = "runs-dir",
+        help = "Directory keeping the artifacts of every run, one subdirectory per cluster and epoch",
+        default_value = "db/runs"
+    )]
+    runs_dir: String,
+
+    #[structopt(
+        long = "scores-db",
+        help = "Path to the sqlite scores history",
+        default_value = "db/score-sqlite3.db"
+    )]
+    scores_db: String,
+
+    #[structopt(
+        long = "fetch-scores-db",
+        help = "Replace the local scores history with the published one before importing"
+    )]
+    fetch_scores_db: bool,
+
+    #[structopt(
+        long = "scores-db-url",
+        help = "Where the published scores history is downloaded from",
+        default_value = "https://github.com/marinade-finance/staking-status/raw/main/scores.sqlite3"
+    )]
+    scores_db_url: String,
+
+    #[structopt(
+        long = "resume",
+        help = "Continue the run of the current epoch skipping the steps already completed"
+    )]
+    resume: bool,
+
+    #[structopt(
+        long = "from-step",
+        help = "Rerun the given step and every step depending on it, implies --resume"
+    )]
+    from_step: Option<Step>,
+
+    #[structopt(
+        long = "commission-samples-dir",
+        help = "Directory of the commission samples taken during the epochs by score-past-epoch sample-commissions, one subdirectory per cluster",
+        default_value = "db/commission-samples"
+    )]
+    commission_samples_dir: String,
+
+    #[structopt(
+        long = "validators-app-cache-dir",
+        help = "Directory of the validators.app responses saved by score-past-epoch, requested at most once a day",
+        default_value = "db/validators-app"
+    )]
+    validators_app_cache_dir: String,
+
+    #[structopt(
+        long = "score-past-epoch-config",
+        help = "Config file of the classification (score-past-epoch --config), the profile is the cluster name",
+        default_value = "config/score-past-epoch.yml"
+    )]
+    score_past_epoch_config: String,
+
+    #[structopt(long = "scoring-model", help = "Scoring model of the classification")]
+    scoring_model: Option<String>,
+
+    #[structopt(
+        long = "score-past-epoch-arg",
+        help = "Extra argument for the classification (score-past-epoch options), can be repeated",
+        allow_hyphen_values = true,
+        number_of_values = 1
+    )]
+    score_past_epoch_args: Vec<String>,
+
+    #[structopt(
+        long = "policy-file",
+        help = "Post-process policy file (process-scores --policy-file), the profile is the cluster name",
+        default_value = "config/score-post-process.yml"
+    )]
+    policy_file: String,
+
+    #[structopt(
+        long = "blacklist-file",
+        help = "Blacklisted validators (process-scores --blacklist-file)",
+        default_value = "config/blacklist.yml"
+    )]
+    blacklist_file: String,
+
+    #[structopt(
+        long = "process-scores-arg",
+        help = "Extra argument for process-scores, can be repeated",
+        allow_hyphen_values = true,
+        number_of_values = 1
+    )]
+    process_scores_args: Vec<String>,
+}
+
+/// Everything the steps of a single epoch run need
+struct EpochRun<'a> {
+    options: &'a RunEpochOptions,
+    common: &'a crate::Common,
+    client: Arc<RpcClient>,
+    json_rpc_url: String,
+    cluster_name: String,
+    epoch: Epoch,
+    run_dir: PathBuf,
+}
+
+impl RunEpochOptions {
+    pub fn process(
+        self,
+        common: crate::Common,
+        client: Arc<RpcClient>,
+        json_rpc_url: String,
+    ) -> anyhow::Result<()> {
+        let cluster_name = match Cluster::from_url(&json_rpc_url) {
+            cluster @ Cluster::Mainnet | cluster @ Cluster::Testnet | cluster @ Cluster::Devnet => {
+                cluster.to_string()
+            }
+            cluster => bail!(
+                "run-epoch supports mainnet-beta, testnet and devnet, {:?} found in {}",
+                cluster,
+                json_rpc_url
+            ),
+        };
+        let epoch = client.get_epoch_info()?.epoch;
+        let run_dir = Path::new(&self.runs_dir).join(format!("{}-epoch-{}", cluster_name, epoch));
+        info!("Epoch {} run directory: {}", epoch, run_dir.display());
+
+        let mut state = match RunState::load(&run_dir)? {
+            Some(state) if self.resume || self.from_step.is_some() => state,
+            Some(_) => bail!(
+                "Epoch {} was already run in {}, use --resume or --from-step to continue it",
+                epoch,
+                run_dir.display()
+            ),
+            None => RunState::new(epoch, cluster_name.clone()),
+        };
+        if let Some(from_step) = self.from_step {
+            state.invalidate(&from_step.with_dependents());
+        }
+        fs::create_dir_all(&run_dir)?;
+
+        let run = EpochRun {
+            options: &self,
+            common: &common,
+            client,
+            json_rpc_url,
+            cluster_name,
+            epoch,
+            run_dir,
+        };
+        for step in Step::ALL.iter().cloned() {
+            if !run.is_enabled(step) {
+                info!("Step {}: disabled", step);
+                continue;
+            }
+            if state.is_completed(step) {
+                info!("Step {}: already completed", step);
+                continue;
+            }
+            info!("Step {}: running", step);
+            run.run_step(step).with_context(|| {
+                format!(
+                    "Step {} of epoch {} failed, rerun with --resume once fixed",
+                    step, epoch
+                )
+            })?;
+            state.mark_completed(step);
+            state.save(&run.run_dir)?;
+        }
+        info!(
+            "Epoch {} completed, artifacts in {}",
+            epoch,
+            run.run_dir.display()
+        );
+        Ok(())
+    }
+}
+
+impl<'a> EpochRun<'a> {
+    fn is_enabled(&self, step: Step) -> bool {
+        match step {
+            Step::FetchScoresDb => self.options.fetch_scores_db,
+            _ => true,
+        }
+    }
+
+    fn run_step(&self, step: Step) -> anyhow::Result<()> {
+        match step {
+            Step::FetchScoresDb => self.fetch_scores_db(),
+            Step::Classify => self.classify(),
+            Step::ImportHistory => self.import_history(),
+            Step::CreateAvgFile => self.create_avg_file(),
+            Step::FetchValidators => self.fetch_validators(),
+            Step::ProcessScores => self.process_scores(),
+            Step::ImportPostProcessed => self.import_post_processed(),
+        }
+    }
+
+    /// Directory where score-past-epoch writes, see `Config::cluster_db_path`
+    fn classification_dir(&self) -> PathBuf {
+        self.run_dir
+            .join(format!("score-all-{}", self.cluster_name))
+    }
+
+    /// Cluster state the classification was computed from, replayable with --snapshot-in
+    fn snapshot_file(&self) -> PathBuf {
+        self.run_dir.join("cluster-snapshot.yml")
+    }
+
+    fn validator_detail_file(&self) -> PathBuf {
+        self.classification_dir()
+            .join(format!("{}-validator-detail.csv", self.cluster_name))
+    }
+
+    /// Validators whose commission was raised for the rewards only, found by the classification
+    fn commission_flipping_file(&self) -> PathBuf {
+        self.classification_dir()
+            .join(format!("{}-commission-flipping.csv", self.cluster_name))
+    }
+
+    /// Average vote latency of the validators, measured by the classification
+    fn vote_latency_file(&self) -> PathBuf {
+        self.classification_dir()
+            .join(format!("{}-vote-latency.csv", self.cluster_name))
+    }
+
+    /// Validators run by the same operator, grouped by the classification
+    fn operator_groups_file(&self) -> PathBuf {
+        self.classification_dir()
+            .join(format!("{}-operator-groups.csv", self.cluster_name))
+    }
+
+    fn avg_file(&self) -> PathBuf {
+        self.run_dir.join("avg.csv")
+    }
+
+    fn validators_file(&self) -> PathBuf {
+        self.run_dir.join("solana-validators.json")
+    }
+
+    /// process-scores saves the APY it estimates next to it, as post-processed-scores.apy.json
+    fn result_file(&self) -> PathBuf {
+        self.run_dir.join("post-processed-scores.csv")
+    }
+
+    fn open_scores_db(&self) -> anyhow::Result<ScoresDb> {
+        ScoresDb::open(&self.options.scores_db)
+    }
+
+    fn download(&self, url: &str, path: &Path) -> anyhow::Result<()> {
+        info!("Downloading {} to {}", url, path.display());
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(300))
+            .build()?;
+        let bytes = client.get(url).send()?.error_for_status()?.bytes()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    fn fetch_scores_db(&self) -> anyhow::Result<()> {
+        self.download(
+            &self.options.scores_db_url,
+            Path::new(&self.options.scores_db),
+        )
+    }
+
+    fn classify(&self) -> anyhow::Result<()> {
+        // score-past-epoch refuses to overwrite a classification, a rerun starts from scratch
+        let classification_dir = self.classification_dir();
+        if classification_dir.exists() {
+            fs::remove_dir_all(&classification_dir)?;
+        }
+
+        let mut args: Vec<String> = vec![
+            "score-past-epoch".into(),
+            "--cluster".into(),
+            self.cluster_name.clone(),
+            "--url".into(),
+            self.json_rpc_url.clone(),
+            "--config".into(),
+            self.options.score_past_epoch_config.clone(),
+            "--db-path".into(),
+            self.run_dir.display().to_string(),
+            "--snapshot-out".into(),
+            self.snapshot_file().display().to_string(),
+            "--commission-samples-path".into(),
+            Path::new(&self.options.commission_samples_dir)
+                .join(&self.cluster_name)
+                .display()
+                .to_string(),
+            "--validators-app-cache".into(),
+            self.options.validators_app_cache_dir.clone(),
+            // import-history reads the validator detail as CSV, --format can't be overridden
+            "--format".into(),
+            "csv".into(),
+        ];
+        args.extend(self.options.score_past_epoch_args.iter().cloned());
+        args.push("score-all".into());
+        if let Some(scoring_model) = &self.options.scoring_model {
+            args.push("--scoring-model".into());
+            args.push(scoring_model.clone());
+        }
+        info!("Classification arguments: {}", args[1..].join(" "));
+
+        let (config, rpc_client) = score_past_epoch::config::get_config_from(args)
+            .map_err(|err| anyhow!("Invalid classification arguments: {}", err))?;
+        let epoch = score_past_epoch::score_epoch(&config, &rpc_client)
+            .map_err(|err| anyhow!("Classification failed: {}", err))?;
+        if epoch != self.epoch {
+            bail!(
+                "The cluster moved to epoch {} during the run of epoch {}, start a new run",
+                epoch,
+                self.epoch
+            );
+        }
+        Ok(())
+    }
+
+    fn import_history(&self) -> anyhow::Result<()> {
+        let validator_detail_file = self.validator_detail_file();
+        if !validator_detail_file.exists() {
+            bail!(
+                "{} not found, the classification skipped the stake adjustments of epoch {}, see its notes in {}",
+                validator_detail_file.display(),
+                self.epoch,
+                self.classification_dir().display()
+            );
+        }
+        let mut db = self.open_scores_db()?;
+        let epoch = import_validator_detail(&mut db, validator_detail_file)?;
+        info!("Epoch {} imported into the scores history", epoch);
+        Ok(())
+    }
+
+    fn create_avg_file(&self) -> anyhow::Result<()> {
+        let db = self.open_scores_db()?;
+        let avg = create_avg(&db, Some(self.epoch))?;
+        write_avg_file(&avg, self.avg_file())
+    }
+
+    /// Saves the vote accounts read by process-scores, in the layout of the
+    /// `solana validators --output json` output, so the run can be reproduced
+    fn fetch_validators(&self) -> anyhow::Result<()> {
+        let cluster_validators = ClusterValidators::fetch(&self.client)?;
+        info!(
+            "{} vote accounts fetched",
+            cluster_validators.validators.len()
+        );
+        cluster_validators.save(self.validators_file())
+    }
+
+    fn process_scores_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "process-scores".into(),
+            "--avg-file".into(),
+            self.avg_file().display().to_string(),
+            "--validators-file".into(),
+            self.validators_file().display().to_string(),
+            "--result-file".into(),
+            self.result_file().display().to_string(),
+            "--policy-file".into(),
+            self.options.policy_file.clone(),
+            "--blacklist-file".into(),
+            self.options.blacklist_file.clone(),
+        ];
+        // a classification that skipped the stake adjustments writes none of these reports
+        for (arg, file) in &[
+            (
+                "--commission-flipping-file",
+                self.commission_flipping_file(),
+            ),
+            ("--vote-latency-file", self.vote_latency_file()),
+            ("--operator-groups-file", self.operator_groups_file()),
+        ] {
+            if file.exists() {
+                args.push(arg.to_string());
+                args.push(file.display().to_string());
+            } else {
+                info!(
+                    "{} not found, process-scores runs without it",
+                    file.display()
+                );
+            }
+        }
+        args.extend(
+            process_scores_preset(&self.cluster_name)
+                .iter()
+                .map(|arg| arg.to_string()),
+        );
+        args.extend(self.options.process_scores_args.iter().cloned());
+        args
+    }
+
+    fn process_scores(&self) -> anyhow::Result<()> {
+        let args = self.process_scores_args();
+        info!("process-scores arguments: {}", args[1..].join(" "));
+        let options = ProcessScoresOptions::from_iter_safe(args)?;
+
+        let cluster = Cluster::from_url(&self.json_rpc_url);
+        let instance = match &self.common.instance {
+            InputPubkey::Auto => InputPubkey::Pubkey(cluster.default_instance()),
+            instance => instance.clone(),
+        };
+        let common = score_post_process::Common {
+            config_file: self.common.config_file.clone(),
+            instance,
+        };
+        options.process(common, self.client.clone(), cluster)
+    }
+
+    fn import_post_processed(&self) -> anyhow::Result<()> {
+        let mut db = self.open_scores_db()?;
+        let epoch = import_post_processed(&mut db, self.result_file())?;
+        info!("Epoch {} post-processed scores imported", epoch);
+        Ok(())
+    }
+}
+
+/// process-scores arguments used for each cluster, besides the policy of the policy file
+fn process_scores_preset(cluster_name: &str) -> &'static [&'static str] {
+    match cluster_name {
+        "mainnet-beta" => &[
+            "--escrow-relocker",
+            "tovt1VkTE2T4caWoeFP6a2xSFoew5mNpd7FWidyyMuk",
+            "--gauge-meister",
+            "mvgmBamY7hDWxLNGLshMoZn8nt2P8tKnKhaBeXMVajZ",
+        ],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_dir(name: &str) -> PathBuf {
+        let run_dir = std::env::temp_dir().join(format!(
+            "delegation-strategy-{}-{}",
+            std::process::id(),
+            name
+        ));
+        if run_dir.exists() {
+            fs::remove_dir_all(&run_dir).unwrap();
+        }
+        run_dir
+    }
+
+    fn epoch_run<'a>(
+        options: &'a RunEpochOptions,
+        common: &'a crate::Common,
+        run_dir: PathBuf,
+    ) -> EpochRun<'a> {
+        let json_rpc_url = "http://api.testnet.solana.com".to_string();
+        EpochRun {
+            options,
+            common,
+            client: Arc::new(RpcClient::new(json_rpc_url.clone())),
+            json_rpc_url,
+            cluster_name: "testnet".into(),
+            epoch: 300,
+            run_dir,
+        }
+    }
+
+    #[test]
+    fn test_process_scores_args_of_a_paused_classification() {
+        let options = RunEpochOptions::from_iter_safe(&["run-epoch"]).unwrap();
+        let common = crate::Common::from_iter_safe(&["delegation-strategy"]).unwrap();
+        let run = epoch_run(&options, &common, run_dir("paused"));
+        // the classification skipped the stake adjustments, it wrote no reports
+        fs::create_dir_all(run.classification_dir()).unwrap();
+
+        let args = run.process_scores_args();
+        for arg in &[
+            "--commission-flipping-file",
+            "--vote-latency-file",
+            "--operator-groups-file",
+        ] {
+            assert!(!args.iter().any(|a| a == arg), "{} passed", arg);
+        }
+        ProcessScoresOptions::from_iter_safe(args).unwrap();
+        fs::remove_dir_all(&run.run_dir).unwrap();
+    }
+
+    #[test]
+    fn test_process_scores_args_of_a_classification() {
+        let options = RunEpochOptions::from_iter_safe(&["run-epoch"]).unwrap();
+        let common = crate::Common::from_iter_safe(&["delegation-strategy"]).unwrap();
+        let run = epoch_run(&options, &common, run_dir("classified"));
+        fs::create_dir_all(run.classification_dir()).unwrap();
+        for file in &[
+            run.commission_flipping_file(),
+            run.vote_latency_file(),
+            run.operator_groups_file(),
+        ] {
+            fs::write(file, "").unwrap();
+        }
+
+        let args = run.process_scores_args();
+        let position = args
+            .iter()
+            .position(|a| a == "--vote-latency-file")
+            .unwrap();
+        assert_eq!(
+            args[position + 1],
+            run.vote_latency_file().display().to_string()
+        );
+        assert!(args.iter().any(|a| a == "--commission-flipping-file"));
+        assert!(args.iter().any(|a| a == "--operator-groups-file"));
+        ProcessScoresOptions::from_iter_safe(args).unwrap();
+        fs::remove_dir_all(&run.run_dir).unwrap();
+    }
+}
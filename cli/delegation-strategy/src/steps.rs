@@ -0,0 +1,202 @@
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Steps of an epoch run, `Step::ALL` lists them in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Step {
+    /// download the scores history published by the staking-status repository
+    FetchScoresDb,
+    /// classify the validators of the past epoch and write the report CSV
+    Classify,
+    /// import the report CSV into the scores history
+    ImportHistory,
+    /// average the scores history into avg.csv
+    CreateAvgFile,
+    /// current epoch credits and delinquency of the validators
+    FetchValidators,
+    /// compute the marinade scores from the averaged scores and the estimated APY
+    ProcessScores,
+    /// import the post-processed scores into the history
+    ImportPostProcessed,
+}
+
+impl Step {
+    pub const ALL: [Step; 7] = [
+        Step::FetchScoresDb,
+        Step::Classify,
+        Step::ImportHistory,
+        Step::CreateAvgFile,
+        Step::FetchValidators,
+        Step::ProcessScores,
+        Step::ImportPostProcessed,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Step::FetchScoresDb => "fetch-scores-db",
+            Step::Classify => "classify",
+            Step::ImportHistory => "import-history",
+            Step::CreateAvgFile => "create-avg-file",
+            Step::FetchValidators => "fetch-validators",
+            Step::ProcessScores => "process-scores",
+            Step::ImportPostProcessed => "import-post-processed",
+        }
+    }
+
+    /// Steps producing the artifacts this step consumes
+    pub fn depends_on(&self) -> &'static [Step] {
+        match self {
+            Step::FetchScoresDb => &[],
+            Step::Classify => &[],
+            Step::ImportHistory => &[Step::FetchScoresDb, Step::Classify],
+            Step::CreateAvgFile => &[Step::ImportHistory],
+            Step::FetchValidators => &[],
+            Step::ProcessScores => &[Step::CreateAvgFile, Step::FetchValidators],
+            Step::ImportPostProcessed => &[Step::ProcessScores],
+        }
+    }
+
+    /// This step and all the steps depending on it, directly or not
+    pub fn with_dependents(self) -> HashSet<Step> {
+        let mut result = HashSet::new();
+        result.insert(self);
+        // ALL is in execution order so a single pass sees the dependencies first
+        for step in Step::ALL.iter() {
+            if step.depends_on().iter().any(|dep| result.contains(dep)) {
+                result.insert(*step);
+            }
+        }
+        result
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Step {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Step::ALL.iter().find(|step| step.name() == s) {
+            Some(step) => Ok(*step),
+            None => bail!(
+                "Unknown step {}, expected one of: {}",
+                s,
+                Step::ALL
+                    .iter()
+                    .map(|step| step.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedStep {
+    pub step: Step,
+    pub finished_at: String,
+}
+
+/// Progress of an epoch run, persisted in the run directory after every step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunState {
+    pub epoch: u64,
+    pub cluster: String,
+    pub completed: Vec<CompletedStep>,
+}
+
+impl RunState {
+    const FILE_NAME: &'static str = "run-state.yml";
+
+    pub fn new(epoch: u64, cluster: String) -> Self {
+        Self {
+            epoch,
+            cluster,
+            completed: vec![],
+        }
+    }
+
+    fn file_name(run_dir: &Path) -> PathBuf {
+        run_dir.join(Self::FILE_NAME)
+    }
+
+    pub fn load(run_dir: &Path) -> anyhow::Result<Option<Self>> {
+        let file_name = Self::file_name(run_dir);
+        if !file_name.exists() {
+            return Ok(None);
+        }
+        let file = fs::File::open(file_name)?;
+        Ok(Some(serde_yaml::from_reader(file)?))
+    }
+
+    pub fn save(&self, run_dir: &Path) -> anyhow::Result<()> {
+        let serialized = serde_yaml::to_string(self)?;
+        fs::write(Self::file_name(run_dir), serialized)?;
+        Ok(())
+    }
+
+    pub fn is_completed(&self, step: Step) -> bool {
+        self.completed.iter().any(|c| c.step == step)
+    }
+
+    pub fn mark_completed(&mut self, step: Step) {
+        self.completed.retain(|c| c.step != step);
+        self.completed.push(CompletedStep {
+            step,
+            finished_at: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+
+    pub fn invalidate(&mut self, steps: &HashSet<Step>) {
+        self.completed.retain(|c| !steps.contains(&c.step));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_are_in_dependency_order() {
+        for (index, step) in Step::ALL.iter().enumerate() {
+            for dep in step.depends_on() {
+                assert!(
+                    Step::ALL[..index].contains(dep),
+                    "{} runs before its dependency {}",
+                    step,
+                    dep
+                );
+            }
+            assert_eq!(step.name().parse::<Step>().unwrap(), *step);
+        }
+    }
+
+    #[test]
+    fn test_with_dependents() {
+        let dependents = Step::FetchValidators.with_dependents();
+        assert_eq!(
+            dependents,
+            [
+                Step::FetchValidators,
+                Step::ProcessScores,
+                Step::ImportPostProcessed
+            ]
+            .iter()
+            .cloned()
+            .collect()
+        );
+        assert_eq!(Step::Classify.with_dependents().len(), 5);
+    }
+}
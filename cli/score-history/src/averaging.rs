@@ -0,0 +1,443 @@
+use crate::records::ScoreRecord;
+use anyhow::bail;
+use cli_common::records::{AvgRecord, ValidatorDetailRecord};
+use std::collections::{HashMap, HashSet};
+
+/// number of epochs (including the imported one) averaged into avg.csv
+pub const AVERAGING_WINDOW_EPOCHS: u64 = 10;
+/// validators with less epochs on record get avg_score = 0
+pub const MIN_SCORE_RECORDS: usize = 5;
+/// validators with less average active stake (SOL) get avg_score = 0
+pub const MIN_AVG_ACTIVE_STAKE: f64 = 100.0;
+/// only the top N validators by avg_score get a pct assigned
+pub const TOP_N_VALIDATORS: usize = 500;
+/// only validators above this adj_credits are used to compute the average for avg_position
+pub const MIN_ADJ_CREDITS_FOR_AVERAGE: i64 = 30_000;
+/// credits discounted per data center concentration percentage point
+const DATA_CENTER_CONCENTRATION_DISCOUNT: f64 = 3.0;
+
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+/// Average ignoring missing values
+#[derive(Default)]
+struct Mean {
+    sum: f64,
+    count: usize,
+}
+
+impl Mean {
+    fn add(&mut self, value: Option<f64>) {
+        if let Some(value) = value {
+            self.sum += value;
+            self.count += 1;
+        }
+    }
+
+    fn get(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.sum / self.count as f64)
+        }
+    }
+}
+
+/// Turns the imported validator-detail rows of a single epoch into `scores` rows,
+/// computing pct, stake_conc, adj_credits, max_commission and the adjusted avg_position
+pub fn enrich_imported(
+    imported: Vec<ValidatorDetailRecord>,
+    previous_max_commission: &HashMap<String, u8>,
+) -> anyhow::Result<Vec<ScoreRecord>> {
+    if let Some(first) = imported.first() {
+        if let Some(other) = imported.iter().find(|r| r.epoch != first.epoch) {
+            bail!(
+                "Imported file contains more than one epoch: {} and {}",
+                first.epoch,
+                other.epoch
+            );
+        }
+    }
+
+    let total_score: u64 = imported.iter().map(|r| r.score).sum();
+    let total_active_stake: f64 = imported.iter().map(|r| r.active_stake).sum();
+
+    let mut records: Vec<ScoreRecord> = imported
+        .into_iter()
+        .map(|r| {
+            let adj_credits = (r.epoch_credits as f64
+                * (100.0
+                    - r.commission as f64
+                    - DATA_CENTER_CONCENTRATION_DISCOUNT * r.data_center_concentration)
+                / 100.0) as i64;
+            let max_commission = r.commission.max(
+                previous_max_commission
+                    .get(&r.vote_address)
+                    .cloned()
+                    .unwrap_or(0),
+            );
+            ScoreRecord {
+                pct: if total_score == 0 {
+                    0.0
+                } else {
+                    round_to(r.score as f64 * 100.0 / total_score as f64, 4)
+                },
+                stake_conc: if total_active_stake == 0.0 {
+                    0.0
+                } else {
+                    round_to(r.active_stake * 100.0 / total_active_stake, 4)
+                },
+                adj_credits,
+                max_commission,
+                avg_position: None,
+                epoch: r.epoch,
+                keybase_id: r.keybase_id,
+                name: r.name,
+                identity: r.identity,
+                vote_address: r.vote_address,
+                score: r.score,
+                commission: r.commission,
+                active_stake: r.active_stake,
+                epoch_credits: r.epoch_credits,
+                data_center_concentration: r.data_center_concentration,
+                data_center_asn: r.data_center_asn,
+                data_center_location: r.data_center_location,
+                can_halt_the_network_group: r.can_halt_the_network_group,
+                stake_state: r.stake_state,
+                stake_state_reason: r.stake_state_reason,
+                www_url: r.www_url,
+                version: r.version,
+            }
+        })
+        .collect();
+
+    // recompute avg_position based on adj_credits
+    let mut reference = Mean::default();
+    for r in records.iter() {
+        if r.adj_credits > MIN_ADJ_CREDITS_FOR_AVERAGE {
+            reference.add(Some(r.adj_credits as f64));
+        }
+    }
+    if let Some(avg_adj_credits) = reference.get() {
+        for r in records.iter_mut() {
+            r.avg_position = Some((r.adj_credits * 50) as f64 / avg_adj_credits);
+        }
+    }
+
+    Ok(records)
+}
+
+/// Computes the averaged scores for the `current` epoch rows using the `history` rows of
+/// the last AVERAGING_WINDOW_EPOCHS epochs (which must include the current epoch).
+/// Result is sorted by avg_score desc.
+pub fn compute_avg(current: &[ScoreRecord], history: &[ScoreRecord]) -> Vec<AvgRecord> {
+    struct Aggregate {
+        score_records: usize,
+        adj_credits: Mean,
+        avg_position: Mean,
+        commission: Mean,
+        epoch_credits: Mean,
+        data_center_concentration: Mean,
+        active_stake: Mean,
+    }
+
+    let epoch = match current.first() {
+        Some(first) => first.epoch,
+        None => return vec![],
+    };
+    let first_epoch = epoch.saturating_sub(AVERAGING_WINDOW_EPOCHS - 1);
+
+    let mut aggregates: HashMap<&str, Aggregate> = HashMap::new();
+    for r in history
+        .iter()
+        .filter(|r| r.epoch >= first_epoch && r.epoch <= epoch)
+    {
+        let aggregate = aggregates
+            .entry(r.vote_address.as_str())
+            .or_insert_with(|| Aggregate {
+                score_records: 0,
+                adj_credits: Mean::default(),
+                avg_position: Mean::default(),
+                commission: Mean::default(),
+                epoch_credits: Mean::default(),
+                data_center_concentration: Mean::default(),
+                active_stake: Mean::default(),
+            });
+        aggregate.score_records += 1;
+        aggregate.adj_credits.add(Some(r.adj_credits as f64));
+        aggregate.avg_position.add(r.avg_position);
+        aggregate.commission.add(Some(r.commission as f64));
+        aggregate.epoch_credits.add(Some(r.epoch_credits as f64));
+        aggregate
+            .data_center_concentration
+            .add(Some(r.data_center_concentration));
+        aggregate.active_stake.add(Some(r.active_stake));
+    }
+
+    // the SQL gave a NULL avg_position a NULL mult, so a NULL avg_score: no pct, and RANK()
+    // ranked them together after the zero scores
+    let without_position: HashSet<&str> = current
+        .iter()
+        .map(|r| r.vote_address.as_str())
+        .filter(|vote_address| {
+            aggregates
+                .get(vote_address)
+                .and_then(|a| a.avg_position.get())
+                .is_none()
+        })
+        .collect();
+
+    let mut avg: Vec<AvgRecord> = current
+        .iter()
+        .map(|r| {
+            let aggregate = aggregates.get(r.vote_address.as_str());
+            let score_records = aggregate.map(|a| a.score_records).unwrap_or(0);
+            let base_score = aggregate
+                .and_then(|a| a.adj_credits.get())
+                .unwrap_or(0.0)
+                .round();
+            let average_position = aggregate.and_then(|a| a.avg_position.get());
+            let mult = match average_position {
+                Some(ap) if ap >= 50.0 => ap - 49.0,
+                Some(_) => 1.0,
+                // NULL in the SQL, no avg_score
+                None => 0.0,
+            };
+            let avg_active_stake = aggregate.and_then(|a| a.active_stake.get()).unwrap_or(0.0);
+            let avg_score = if r.score == 0
+                || mult <= 0.0
+                || score_records < MIN_SCORE_RECORDS
+                || avg_active_stake < MIN_AVG_ACTIVE_STAKE
+            {
+                0.0
+            } else {
+                (base_score * mult).round()
+            };
+            let avg_commission =
+                round_to(aggregate.and_then(|a| a.commission.get()).unwrap_or(0.0), 2);
+
+            AvgRecord {
+                rank: 0,
+                pct: 0.0,
+                epoch: r.epoch,
+                keybase_id: r.keybase_id.clone(),
+                name: r.name.clone(),
+                vote_address: r.vote_address.clone(),
+                score: 0,
+                average_position: average_position.unwrap_or(0.0),
+                epoch_credits: aggregate
+                    .and_then(|a| a.epoch_credits.get())
+                    .unwrap_or(0.0)
+                    .round() as u64,
+                commission: avg_commission as u8,
+                max_commission: r.max_commission,
+                data_center_concentration: aggregate
+                    .and_then(|a| a.data_center_concentration.get())
+                    .unwrap_or(0.0),
+                data_center_asn: r.data_center_asn,
+                data_center_location: r.data_center_location.clone(),
+                base_score,
+                mult,
+                avg_score,
+                avg_active_stake,
+                identity: r.identity.clone(),
+                can_halt_the_network_group: r.can_halt_the_network_group,
+                stake_conc: r.stake_conc,
+                version: r.version.clone(),
+                url: r.www_url.clone(),
+            }
+        })
+        .collect();
+
+    // sort by avg_score desc, ties by base_score desc, the rows without avg_position last
+    let has_position = |a: &AvgRecord| !without_position.contains(a.vote_address.as_str());
+    avg.sort_by(|a, b| {
+        has_position(b)
+            .cmp(&has_position(a))
+            .then(b.avg_score.partial_cmp(&a.avg_score).unwrap())
+            .then(b.base_score.partial_cmp(&a.base_score).unwrap())
+    });
+
+    // rank with gaps on ties, as RANK() does
+    for index in 0..avg.len() {
+        let tie = index > 0 && {
+            let (previous, a) = (&avg[index - 1], &avg[index]);
+            has_position(previous) == has_position(a)
+                && (!has_position(a) || previous.avg_score == a.avg_score)
+        };
+        avg[index].rank = if tie {
+            avg[index - 1].rank
+        } else {
+            index as u32 + 1
+        };
+    }
+
+    // set pct ONLY ON selected TOP validators
+    let top_n = avg.len().min(TOP_N_VALIDATORS);
+    let top_sum: f64 = avg[..top_n].iter().map(|a| a.avg_score).sum();
+    if top_sum > 0.0 {
+        for a in avg[..top_n].iter_mut() {
+            a.pct = a.avg_score / top_sum * 100.0;
+        }
+    }
+    for a in avg.iter_mut() {
+        a.score = if a.pct > 0.0 { a.avg_score as u64 } else { 0 };
+    }
+
+    avg
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn detail(
+        vote_address: &str,
+        epoch: u64,
+        score: u64,
+        epoch_credits: u64,
+    ) -> ValidatorDetailRecord {
+        ValidatorDetailRecord {
+            epoch,
+            keybase_id: String::new(),
+            name: vote_address.into(),
+            identity: format!("identity-{}", vote_address),
+            vote_address: vote_address.into(),
+            score,
+            average_position: 50.0,
+            commission: 0,
+            active_stake: 1_000.0,
+            epoch_credits,
+            data_center_concentration: 0.0,
+            data_center_asn: 0,
+            data_center_location: String::new(),
+            can_halt_the_network_group: false,
+            stake_state: "Bonus".into(),
+            stake_state_reason: String::new(),
+            www_url: String::new(),
+            version: "1.10.32".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_enrich_imported() {
+        let mut a = detail("a", 100, 300, 400_000);
+        a.commission = 10;
+        a.data_center_concentration = 5.0;
+        let b = detail("b", 100, 100, 200_000);
+        let previous_max_commission = HashMap::from([("b".to_string(), 7u8)]);
+
+        let records = enrich_imported(vec![a, b], &previous_max_commission).unwrap();
+
+        assert_eq!(records[0].pct, 75.0);
+        assert_eq!(records[1].pct, 25.0);
+        assert_eq!(records[0].stake_conc, 50.0);
+        // 400_000 * (100 - 10 - 3 * 5) / 100
+        assert_eq!(records[0].adj_credits, 300_000);
+        assert_eq!(records[0].max_commission, 10);
+        assert_eq!(records[1].max_commission, 7);
+        assert_eq!(records[0].avg_position, Some(60.0));
+        assert_eq!(records[1].avg_position, Some(40.0));
+    }
+
+    #[test]
+    fn test_enrich_imported_rejects_mixed_epochs() {
+        assert!(enrich_imported(
+            vec![detail("a", 100, 1, 1), detail("b", 101, 1, 1)],
+            &HashMap::new()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_compute_avg() {
+        let mut history = vec![];
+        for epoch in 91..=100 {
+            history.extend(
+                enrich_imported(
+                    vec![
+                        detail("a", epoch, 10, 400_000),
+                        detail("b", epoch, 10, 200_000),
+                        detail("c", epoch, 0, 300_000),
+                    ],
+                    &HashMap::new(),
+                )
+                .unwrap(),
+            );
+        }
+        // too few epochs on record
+        history
+            .extend(enrich_imported(vec![detail("d", 100, 10, 300_000)], &HashMap::new()).unwrap());
+        let current: Vec<ScoreRecord> =
+            history.iter().filter(|r| r.epoch == 100).cloned().collect();
+
+        let avg = compute_avg(&current, &history);
+
+        let by_vote: HashMap<&str, &AvgRecord> =
+            avg.iter().map(|a| (a.vote_address.as_str(), a)).collect();
+        assert_eq!(avg[0].vote_address, "a");
+        assert_eq!(by_vote["a"].rank, 1);
+        assert_eq!(by_vote["a"].base_score, 400_000.0);
+        // avg_position = 400_000 * 50 / 300_000
+        assert!((by_vote["a"].mult - (400_000.0 * 50.0 / 300_000.0 - 49.0)).abs() < 1e-9);
+        assert_eq!(by_vote["b"].mult, 1.0);
+        assert_eq!(by_vote["b"].avg_score, 200_000.0);
+        assert_eq!(by_vote["c"].avg_score, 0.0);
+        assert_eq!(by_vote["d"].avg_score, 0.0);
+        assert_eq!(by_vote["c"].rank, by_vote["d"].rank);
+        assert_eq!(by_vote["c"].score, 0);
+        let pct_sum: f64 = avg.iter().map(|a| a.pct).sum();
+        assert!((pct_sum - 100.0).abs() < 1e-9);
+        assert_eq!(by_vote["b"].score, 200_000);
+    }
+
+    #[test]
+    fn test_compute_avg_without_avg_position() {
+        let mut history = vec![];
+        for epoch in 91..=100 {
+            history.extend(
+                enrich_imported(
+                    vec![
+                        detail("a", epoch, 10, 400_000),
+                        detail("b", epoch, 10, 200_000),
+                        detail("c", epoch, 0, 300_000),
+                        detail("d", epoch, 10, 500_000),
+                        detail("e", epoch, 10, 300_000),
+                    ],
+                    &HashMap::new(),
+                )
+                .unwrap(),
+            );
+        }
+        // rows imported without avg_position, NULL in the scores history
+        for r in history
+            .iter_mut()
+            .filter(|r| r.vote_address == "d" || r.vote_address == "e")
+        {
+            r.avg_position = None;
+        }
+        let current: Vec<ScoreRecord> =
+            history.iter().filter(|r| r.epoch == 100).cloned().collect();
+
+        let avg = compute_avg(&current, &history);
+
+        assert_eq!(
+            avg.iter()
+                .map(|a| (a.vote_address.as_str(), a.rank))
+                .collect::<Vec<_>>(),
+            vec![("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 4)]
+        );
+        let d = &avg[3];
+        assert_eq!(d.average_position, 0.0);
+        assert_eq!(d.mult, 0.0);
+        assert_eq!(d.avg_score, 0.0);
+        assert_eq!(d.pct, 0.0);
+        assert_eq!(d.score, 0);
+        // the share of d and e goes to the validators with an avg_position
+        let pct_sum: f64 = avg[..2].iter().map(|a| a.pct).sum();
+        assert!((pct_sum - 100.0).abs() < 1e-9);
+    }
+}
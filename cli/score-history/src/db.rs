@@ -0,0 +1,540 @@
+use crate::records::ScoreRecord;
+use cli_common::records::PostProcessedRecord;
+use log::info;
+use rusqlite::{params, types::ValueRef, Connection, Row};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+const CREATE_SCORES_TABLE: &str = "CREATE TABLE IF NOT EXISTS scores(
+  epoch INT,
+  keybase_id TEXT,
+  name TEXT,
+  identity TEXT,
+  vote_address TEXT,
+  score INTEGER,
+  avg_position REAL,
+  commission SHORT,
+  active_stake INTEGER,
+  epoch_credits INTEGER,
+  data_center_concentration DOUBLE,
+  data_center_asn INTEGER,
+  data_center_location TEXT,
+  can_halt_the_network_group BOOL,
+  stake_state TEXT,
+  stake_state_reason TEXT,
+  www_url TEXT,
+  version TEXT,
+  pct FLOAT,
+  stake_conc FLOAT,
+  adj_credits INTEGER,
+  max_commission SHORT
+)";
+
+const SCORES_COLUMNS: &str = "epoch, keybase_id, name, identity, vote_address, score, avg_position, commission, active_stake, epoch_credits, data_center_concentration, data_center_asn, data_center_location, can_halt_the_network_group, stake_state, stake_state_reason, www_url, version, pct, stake_conc, adj_credits, max_commission";
+
+const CREATE_POST_PROCESSED_TABLE: &str = "CREATE TABLE IF NOT EXISTS scores2(
+  epoch INT,
+  rank INT,
+  score INTEGER,
+  marinade_score INTEGER,
+  collateral_score INTEGER,
+  collateral_shares INTEGER,
+  vote_score INTEGER,
+  votes_read INTEGER,
+  votes_effective INTEGER,
+  name TEXT,
+  credits_observed INTEGER,
+  vote_address TEXT,
+  commission INTEGER,
+  max_commission INTEGER,
+  average_position DOUBLE,
+  data_center_concentration DOUBLE,
+  data_center_asn SHORT,
+  data_center_location TEXT,
+  avg_active_stake INTEGER,
+  apy DOUBLE,
+  delinquent BOOL,
+  this_epoch_credits INTEGER,
+  pct DOUBLE,
+  marinade_staked DOUBLE,
+  should_have DOUBLE,
+  remove_level INTEGER,
+  remove_level_reason TEXT,
+  under_nakamoto_coefficient BOOLEAN,
+  keybase_id TEXT,
+  identity TEXT,
+  stake_concentration DOUBLE,
+  base_score INTEGER,
+  url TEXT,
+  version TEXT
+)";
+
+const POST_PROCESSED_COLUMNS: &str = "epoch, rank, score, marinade_score, collateral_score, collateral_shares, vote_score, votes_read, votes_effective, name, credits_observed, vote_address, commission, max_commission, average_position, data_center_concentration, data_center_asn, data_center_location, avg_active_stake, apy, delinquent, this_epoch_credits, pct, marinade_staked, should_have, remove_level, remove_level_reason, under_nakamoto_coefficient, keybase_id, identity, stake_concentration, base_score, url, version";
+
+/// Columns added to scores2 after the first version of the table, with the value given to the
+/// records imported before, see [ScoresDb::migrate_post_processed]
+const POST_PROCESSED_ADDED_COLUMNS: &[(&str, &str)] =
+    &[("collateral_score", "0"), ("collateral_shares", "0")];
+
+/// Scores history database, compatible with the one historically maintained by the `sqlite3` scripts
+pub struct ScoresDb {
+    connection: Connection,
+}
+
+// The history was filled by the sqlite3 CSV import, so values can have any storage class,
+// e.g. booleans are stored as 'true'/'false' text. Read leniently.
+fn get_f64(row: &Row, index: usize) -> rusqlite::Result<Option<f64>> {
+    Ok(match row.get_ref(index)? {
+        ValueRef::Integer(value) => Some(value as f64),
+        ValueRef::Real(value) => Some(value),
+        ValueRef::Text(value) => std::str::from_utf8(value)
+            .ok()
+            .and_then(|value| value.trim().parse().ok()),
+        ValueRef::Null | ValueRef::Blob(_) => None,
+    })
+}
+
+fn get_string(row: &Row, index: usize) -> rusqlite::Result<String> {
+    Ok(match row.get_ref(index)? {
+        ValueRef::Integer(value) => value.to_string(),
+        ValueRef::Real(value) => value.to_string(),
+        ValueRef::Text(value) => String::from_utf8_lossy(value).into_owned(),
+        ValueRef::Null | ValueRef::Blob(_) => String::new(),
+    })
+}
+
+fn get_bool(row: &Row, index: usize) -> rusqlite::Result<bool> {
+    Ok(match row.get_ref(index)? {
+        ValueRef::Integer(value) => value != 0,
+        ValueRef::Real(value) => value != 0.0,
+        ValueRef::Text(value) => value.eq_ignore_ascii_case(b"true") || value == b"1",
+        ValueRef::Null | ValueRef::Blob(_) => false,
+    })
+}
+
+fn score_record_from_row(row: &Row) -> rusqlite::Result<ScoreRecord> {
+    Ok(ScoreRecord {
+        epoch: get_f64(row, 0)?.unwrap_or(0.0) as u64,
+        keybase_id: get_string(row, 1)?,
+        name: get_string(row, 2)?,
+        identity: get_string(row, 3)?,
+        vote_address: get_string(row, 4)?,
+        score: get_f64(row, 5)?.unwrap_or(0.0) as u64,
+        avg_position: get_f64(row, 6)?,
+        commission: get_f64(row, 7)?.unwrap_or(0.0) as u8,
+        active_stake: get_f64(row, 8)?.unwrap_or(0.0),
+        epoch_credits: get_f64(row, 9)?.unwrap_or(0.0) as u64,
+        data_center_concentration: get_f64(row, 10)?.unwrap_or(0.0),
+        data_center_asn: get_f64(row, 11)?.unwrap_or(0.0) as u64,
+        data_center_location: get_string(row, 12)?,
+        can_halt_the_network_group: get_bool(row, 13)?,
+        stake_state: get_string(row, 14)?,
+        stake_state_reason: get_string(row, 15)?,
+        www_url: get_string(row, 16)?,
+        version: get_string(row, 17)?,
+        pct: get_f64(row, 18)?.unwrap_or(0.0),
+        stake_conc: get_f64(row, 19)?.unwrap_or(0.0),
+        adj_credits: get_f64(row, 20)?.unwrap_or(0.0) as i64,
+        max_commission: get_f64(row, 21)?.unwrap_or(0.0) as u8,
+    })
+}
+
+fn post_processed_record_from_row(row: &Row) -> rusqlite::Result<PostProcessedRecord> {
+    Ok(PostProcessedRecord {
+        epoch: get_f64(row, 0)?.unwrap_or(0.0) as u64,
+        rank: get_f64(row, 1)?.unwrap_or(0.0) as u32,
+        score: get_f64(row, 2)?.unwrap_or(0.0) as u32,
+        marinade_score: get_f64(row, 3)?.unwrap_or(0.0) as u32,
+        collateral_score: get_f64(row, 4)?.unwrap_or(0.0) as u32,
+        collateral_shares: get_f64(row, 5)?.unwrap_or(0.0) as u64,
+        vote_score: get_f64(row, 6)?.unwrap_or(0.0) as u32,
+        votes_read: get_f64(row, 7)?.unwrap_or(0.0) as u64,
+        votes_effective: get_f64(row, 8)?.unwrap_or(0.0) as u64,
+        name: get_string(row, 9)?,
+        credits_observed: get_f64(row, 10)?.unwrap_or(0.0) as u64,
+        vote_address: get_string(row, 11)?,
+        commission: get_f64(row, 12)?.unwrap_or(0.0) as u8,
+        max_commission: get_f64(row, 13)?.unwrap_or(0.0) as u8,
+        average_position: get_f64(row, 14)?.unwrap_or(0.0),
+        data_center_concentration: get_f64(row, 15)?.unwrap_or(0.0),
+        data_center_asn: get_f64(row, 16)?.unwrap_or(0.0) as u64,
+        data_center_location: get_string(row, 17)?,
+        avg_active_stake: get_f64(row, 18)?.unwrap_or(0.0),
+        apy: get_f64(row, 19)?,
+        delinquent: get_bool(row, 20)?,
+        this_epoch_credits: get_f64(row, 21)?.unwrap_or(0.0) as u64,
+        pct: get_f64(row, 22)?.unwrap_or(0.0),
+        marinade_staked: get_f64(row, 23)?.unwrap_or(0.0),
+        should_have: get_f64(row, 24)?.unwrap_or(0.0),
+        remove_level: get_f64(row, 25)?.unwrap_or(0.0) as u8,
+        remove_level_reason: get_string(row, 26)?,
+        under_nakamoto_coefficient: get_bool(row, 27)?,
+        keybase_id: get_string(row, 28)?,
+        identity: get_string(row, 29)?,
+        stake_concentration: get_f64(row, 30)?.unwrap_or(0.0),
+        base_score: get_f64(row, 31)?.unwrap_or(0.0) as u64,
+        url: get_string(row, 32)?,
+        version: get_string(row, 33)?,
+        // not kept in scores2
+        ..PostProcessedRecord::default()
+    })
+}
+
+impl ScoresDb {
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        info!("Opening scores db {}", path.as_ref().display());
+        let connection = Connection::open(path)?;
+        Self::new(connection)
+    }
+
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(connection: Connection) -> anyhow::Result<Self> {
+        connection.execute(CREATE_SCORES_TABLE, [])?;
+        connection.execute(CREATE_POST_PROCESSED_TABLE, [])?;
+        Ok(Self { connection })
+    }
+
+    /// max commission ever recorded per vote address
+    pub fn max_commission_by_vote_address(&self) -> anyhow::Result<HashMap<String, u8>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT vote_address, MAX(commission) FROM scores GROUP BY vote_address")?;
+        let rows = statement.query_map([], |row| {
+            Ok((get_string(row, 0)?, get_f64(row, 1)?.unwrap_or(0.0) as u8))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    pub fn latest_epoch(&self) -> anyhow::Result<Option<u64>> {
+        Ok(self
+            .connection
+            .query_row(
+                "SELECT MAX(CAST(epoch AS INTEGER)) FROM scores",
+                [],
+                |row| row.get::<_, Option<i64>>(0),
+            )?
+            .map(|epoch| epoch as u64))
+    }
+
+    /// Replaces all the records of the epoch with `records`
+    pub fn replace_epoch(&mut self, epoch: u64, records: &[ScoreRecord]) -> anyhow::Result<()> {
+        let transaction = self.connection.transaction()?;
+        let deleted = transaction.execute("DELETE FROM scores WHERE epoch = ?1", params![epoch])?;
+        if deleted > 0 {
+            info!("Replacing {} records of epoch {}", deleted, epoch);
+        }
+        {
+            let mut statement = transaction.prepare(&format!(
+                "INSERT INTO scores ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)",
+                SCORES_COLUMNS
+            ))?;
+            for r in records {
+                statement.execute(params![
+                    r.epoch,
+                    r.keybase_id,
+                    r.name,
+                    r.identity,
+                    r.vote_address,
+                    r.score,
+                    r.avg_position,
+                    r.commission,
+                    r.active_stake,
+                    r.epoch_credits,
+                    r.data_center_concentration,
+                    r.data_center_asn,
+                    r.data_center_location,
+                    // keep the representation used by the sqlite3 CSV import
+                    r.can_halt_the_network_group.to_string(),
+                    r.stake_state,
+                    r.stake_state_reason,
+                    r.www_url,
+                    r.version,
+                    r.pct,
+                    r.stake_conc,
+                    r.adj_credits,
+                    r.max_commission,
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Replaces all the post-processed records (table scores2) of the epoch with `records`
+    pub fn replace_post_processed_epoch(
+        &mut self,
+        epoch: u64,
+        records: &[PostProcessedRecord],
+    ) -> anyhow::Result<()> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute("DELETE FROM scores2 WHERE epoch = ?1", params![epoch])?;
+        {
+            let placeholders = (1..=34)
+                .map(|index| format!("?{}", index))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut statement = transaction.prepare(&format!(
+                "INSERT INTO scores2 ({}) VALUES ({})",
+                POST_PROCESSED_COLUMNS, placeholders
+            ))?;
+            for r in records {
+                statement.execute(params![
+                    r.epoch,
+                    r.rank,
+                    r.score,
+                    r.marinade_score,
+                    r.collateral_score,
+                    r.collateral_shares,
+                    r.vote_score,
+                    r.votes_read,
+                    r.votes_effective,
+                    r.name,
+                    r.credits_observed,
+                    r.vote_address,
+                    r.commission,
+                    r.max_commission,
+                    r.average_position,
+                    r.data_center_concentration,
+                    r.data_center_asn,
+                    r.data_center_location,
+                    r.avg_active_stake,
+                    r.apy,
+                    r.delinquent.to_string(),
+                    r.this_epoch_credits,
+                    r.pct,
+                    r.marinade_staked,
+                    r.should_have,
+                    r.remove_level,
+                    r.remove_level_reason,
+                    r.under_nakamoto_coefficient.to_string(),
+                    r.keybase_id,
+                    r.identity,
+                    r.stake_concentration,
+                    r.base_score,
+                    r.url,
+                    r.version,
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    pub fn latest_post_processed_epoch(&self) -> anyhow::Result<Option<u64>> {
+        Ok(self
+            .connection
+            .query_row(
+                "SELECT MAX(CAST(epoch AS INTEGER)) FROM scores2",
+                [],
+                |row| row.get::<_, Option<i64>>(0),
+            )?
+            .map(|epoch| epoch as u64))
+    }
+
+    /// Loads the post-processed records (table scores2) of the epoch, by rank
+    pub fn load_post_processed_epoch(
+        &self,
+        epoch: u64,
+    ) -> anyhow::Result<Vec<PostProcessedRecord>> {
+        let mut statement = self.connection.prepare(&format!(
+            "SELECT {} FROM scores2 WHERE epoch = ?1 ORDER BY CAST(rank AS INTEGER)",
+            POST_PROCESSED_COLUMNS
+        ))?;
+        let rows = statement.query_map(params![epoch], post_processed_record_from_row)?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Adds the columns missing in a scores2 table created before them, formerly the
+    /// `scripts/migrate` sqlite3 script. Returns the added columns.
+    pub fn migrate_post_processed(&mut self) -> anyhow::Result<Vec<&'static str>> {
+        let existing_columns = {
+            let mut statement = self.connection.prepare("PRAGMA table_info(scores2)")?;
+            let rows = statement.query_map([], |row| row.get::<_, String>(1))?;
+            rows.collect::<Result<HashSet<_>, _>>()?
+        };
+        let added_columns: Vec<&'static str> = POST_PROCESSED_ADDED_COLUMNS
+            .iter()
+            .map(|(column, _)| *column)
+            .filter(|column| !existing_columns.contains(*column))
+            .collect();
+        if added_columns.is_empty() {
+            return Ok(added_columns);
+        }
+
+        let values = POST_PROCESSED_COLUMNS
+            .split(", ")
+            .map(|column| {
+                match POST_PROCESSED_ADDED_COLUMNS
+                    .iter()
+                    .find(|(added, _)| *added == column && added_columns.contains(added))
+                {
+                    Some((added, value)) => format!("{} AS {}", value, added),
+                    None => column.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let transaction = self.connection.transaction()?;
+        transaction.execute_batch(&format!(
+            "ALTER TABLE scores2 RENAME TO scores2_migration;
+            {};
+            INSERT INTO scores2 ({}) SELECT {} FROM scores2_migration;
+            DROP TABLE scores2_migration;",
+            CREATE_POST_PROCESSED_TABLE, POST_PROCESSED_COLUMNS, values
+        ))?;
+        transaction.commit()?;
+        info!("Columns {} added to scores2", added_columns.join(", "));
+        Ok(added_columns)
+    }
+
+    /// Loads the records of the epochs in [from_epoch, to_epoch]
+    pub fn load_epochs(&self, from_epoch: u64, to_epoch: u64) -> anyhow::Result<Vec<ScoreRecord>> {
+        let mut statement = self.connection.prepare(&format!(
+            "SELECT {} FROM scores WHERE epoch BETWEEN ?1 AND ?2",
+            SCORES_COLUMNS
+        ))?;
+        let rows = statement.query_map(params![from_epoch, to_epoch], score_record_from_row)?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record(vote_address: &str, epoch: u64, commission: u8) -> ScoreRecord {
+        ScoreRecord {
+            epoch,
+            keybase_id: "keybase".into(),
+            name: "name".into(),
+            identity: "identity".into(),
+            vote_address: vote_address.into(),
+            score: 10,
+            avg_position: Some(50.5),
+            commission,
+            active_stake: 1234.5,
+            epoch_credits: 300_000,
+            data_center_concentration: 1.5,
+            data_center_asn: 24940,
+            data_center_location: "DE-Falkenstein".into(),
+            can_halt_the_network_group: true,
+            stake_state: "Bonus".into(),
+            stake_state_reason: "reason".into(),
+            www_url: "https://example.com".into(),
+            version: "1.10.32".into(),
+            pct: 0.5,
+            stake_conc: 0.25,
+            adj_credits: 290_000,
+            max_commission: commission,
+        }
+    }
+
+    #[test]
+    fn test_replace_and_load() {
+        let mut db = ScoresDb::open_in_memory().unwrap();
+        db.replace_epoch(100, &[record("a", 100, 5), record("b", 100, 7)])
+            .unwrap();
+        db.replace_epoch(101, &[record("a", 101, 8)]).unwrap();
+        db.replace_epoch(101, &[record("a", 101, 3)]).unwrap();
+
+        assert_eq!(db.latest_epoch().unwrap(), Some(101));
+        assert_eq!(db.load_epochs(101, 101).unwrap(), vec![record("a", 101, 3)]);
+        assert_eq!(db.load_epochs(90, 101).unwrap().len(), 3);
+
+        let max_commission = db.max_commission_by_vote_address().unwrap();
+        assert_eq!(max_commission["a"], 5);
+        assert_eq!(max_commission["b"], 7);
+    }
+
+    fn post_processed_record(vote_address: &str, epoch: u64, rank: u32) -> PostProcessedRecord {
+        PostProcessedRecord {
+            epoch,
+            rank,
+            score: 1000,
+            marinade_score: 900,
+            collateral_score: 100,
+            collateral_shares: 5,
+            name: "name".into(),
+            vote_address: vote_address.into(),
+            commission: 5,
+            max_commission: 7,
+            average_position: 51.5,
+            avg_active_stake: 1234.5,
+            apy: Some(7.25),
+            delinquent: true,
+            pct: 0.5,
+            marinade_staked: 100.5,
+            should_have: 120.0,
+            remove_level: 1,
+            remove_level_reason: "reason".into(),
+            version: "1.13.1".into(),
+            ..PostProcessedRecord::default()
+        }
+    }
+
+    #[test]
+    fn test_replace_and_load_post_processed() {
+        let mut db = ScoresDb::open_in_memory().unwrap();
+        assert_eq!(db.latest_post_processed_epoch().unwrap(), None);
+        db.replace_post_processed_epoch(
+            100,
+            &[
+                post_processed_record("b", 100, 2),
+                post_processed_record("a", 100, 1),
+            ],
+        )
+        .unwrap();
+        db.replace_post_processed_epoch(101, &[post_processed_record("a", 101, 1)])
+            .unwrap();
+
+        assert_eq!(db.latest_post_processed_epoch().unwrap(), Some(101));
+        assert_eq!(
+            db.load_post_processed_epoch(100).unwrap(),
+            vec![
+                post_processed_record("a", 100, 1),
+                post_processed_record("b", 100, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migrate_post_processed() {
+        let mut db = ScoresDb::open_in_memory().unwrap();
+        assert!(db.migrate_post_processed().unwrap().is_empty());
+
+        // scores2 as created before the collateral columns
+        db.connection
+            .execute_batch(&format!(
+                "DROP TABLE scores2;
+                {};
+                INSERT INTO scores2 (epoch, rank, name, vote_address, delinquent)
+                    VALUES (100, 1, 'name', 'a', 'false');",
+                CREATE_POST_PROCESSED_TABLE.replace(
+                    "  collateral_score INTEGER,\n  collateral_shares INTEGER,\n",
+                    ""
+                )
+            ))
+            .unwrap();
+        assert_eq!(
+            db.migrate_post_processed().unwrap(),
+            vec!["collateral_score", "collateral_shares"]
+        );
+        assert!(db.migrate_post_processed().unwrap().is_empty());
+
+        let records = db.load_post_processed_epoch(100).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].vote_address, "a");
+        assert_eq!(records[0].collateral_score, 0);
+        db.replace_post_processed_epoch(101, &[post_processed_record("a", 101, 1)])
+            .unwrap();
+        assert_eq!(
+            db.load_post_processed_epoch(101).unwrap()[0].collateral_score,
+            100
+        );
+    }
+}
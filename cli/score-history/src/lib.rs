@@ -0,0 +1,137 @@
+pub mod averaging;
+pub mod db;
+pub mod records;
+
+use averaging::{compute_avg, enrich_imported, AVERAGING_WINDOW_EPOCHS};
+use cli_common::records::{
+    read_artifact, write_artifact, AvgRecord, PostProcessedRecord, ValidatorDetailRecord,
+};
+use db::ScoresDb;
+use log::info;
+use std::path::Path;
+
+/// Imports a `{cluster}-validator-detail.csv` into the scores history, replacing
+/// previously imported records of the same epoch. Returns the imported epoch.
+pub fn import_validator_detail<P: AsRef<Path>>(
+    db: &mut ScoresDb,
+    validator_detail_file: P,
+) -> anyhow::Result<u64> {
+    info!(
+        "Importing scores from {}",
+        validator_detail_file.as_ref().display()
+    );
+    let imported: Vec<ValidatorDetailRecord> = read_artifact(validator_detail_file)?;
+    let epoch = match imported.first() {
+        Some(first) => first.epoch,
+        None => anyhow::bail!("No records found in the validator detail file"),
+    };
+
+    let records = enrich_imported(imported, &db.max_commission_by_vote_address()?)?;
+
+    let total_active_stake: f64 = records.iter().map(|r| r.active_stake).sum();
+    let scored = records.iter().filter(|r| r.pct > 0.0).count();
+    info!(
+        "epoch {}: {} validators, total staked {}, {} with non-zero score",
+        epoch,
+        records.len(),
+        total_active_stake,
+        scored
+    );
+
+    db.replace_epoch(epoch, &records)?;
+    Ok(epoch)
+}
+
+/// Imports a `post-processed-scores.csv` into the post-processed history (table scores2),
+/// replacing previously imported records of the same epoch. Returns the imported epoch.
+pub fn import_post_processed<P: AsRef<Path>>(
+    db: &mut ScoresDb,
+    post_processed_file: P,
+) -> anyhow::Result<u64> {
+    info!(
+        "Importing post-processed scores from {}",
+        post_processed_file.as_ref().display()
+    );
+    let records: Vec<PostProcessedRecord> = read_artifact(post_processed_file)?;
+    let epoch = match records.first() {
+        Some(first) => first.epoch,
+        None => anyhow::bail!("No records found in the post-processed scores file"),
+    };
+    if let Some(other) = records.iter().find(|r| r.epoch != epoch) {
+        anyhow::bail!(
+            "Post-processed scores file contains more than one epoch: {} and {}",
+            epoch,
+            other.epoch
+        );
+    }
+
+    db.replace_post_processed_epoch(epoch, &records)?;
+    info!("epoch {}: {} post-processed records", epoch, records.len());
+    Ok(epoch)
+}
+
+/// Writes the post-processed records of `epoch` (latest imported epoch if None) kept in the
+/// history, by rank. Returns the exported epoch.
+pub fn export_post_processed<P: AsRef<Path>>(
+    db: &ScoresDb,
+    epoch: Option<u64>,
+    path: P,
+) -> anyhow::Result<u64> {
+    let epoch =
+        match epoch.map_or_else(|| db.latest_post_processed_epoch(), |epoch| Ok(Some(epoch)))? {
+            Some(epoch) => epoch,
+            None => anyhow::bail!("The post-processed history is empty"),
+        };
+    let records = db.load_post_processed_epoch(epoch)?;
+    if records.is_empty() {
+        anyhow::bail!(
+            "No post-processed records of epoch {} in the history",
+            epoch
+        );
+    }
+    info!(
+        "Writing {} post-processed records of epoch {} to {}",
+        records.len(),
+        epoch,
+        path.as_ref().display()
+    );
+    write_artifact(path, &records)?;
+    Ok(epoch)
+}
+
+/// Computes the averaged scores of `epoch` (latest imported epoch if None)
+pub fn create_avg(db: &ScoresDb, epoch: Option<u64>) -> anyhow::Result<Vec<AvgRecord>> {
+    let epoch = match epoch.map_or_else(|| db.latest_epoch(), |epoch| Ok(Some(epoch)))? {
+        Some(epoch) => epoch,
+        None => anyhow::bail!("The scores history is empty"),
+    };
+    let history = db.load_epochs(epoch.saturating_sub(AVERAGING_WINDOW_EPOCHS - 1), epoch)?;
+    let current: Vec<_> = history
+        .iter()
+        .filter(|r| r.epoch == epoch)
+        .cloned()
+        .collect();
+    if current.is_empty() {
+        anyhow::bail!("No records of epoch {} in the scores history", epoch);
+    }
+
+    let avg = compute_avg(&current, &history);
+    info!(
+        "epoch {}: {} averaged records, {} with pct assigned",
+        epoch,
+        avg.len(),
+        avg.iter().filter(|a| a.pct > 0.0).count()
+    );
+    for a in avg.iter().filter(|a| a.pct > 0.0).take(15) {
+        info!(
+            "#{} {} pct:{:.4} avg_score:{} mult:{:.4} avg_pos:{:.4} epoch_credits:{}",
+            a.rank, a.name, a.pct, a.avg_score, a.mult, a.average_position, a.epoch_credits
+        );
+    }
+    Ok(avg)
+}
+
+pub fn write_avg_file<P: AsRef<Path>>(avg: &[AvgRecord], path: P) -> anyhow::Result<()> {
+    info!("Writing {}", path.as_ref().display());
+    write_artifact(path, avg)
+}
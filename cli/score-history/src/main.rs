@@ -0,0 +1,103 @@
+#![cfg_attr(not(debug_assertions), deny(warnings))]
+
+use log::info;
+use score_history::{
+    create_avg, db::ScoresDb, export_post_processed, import_post_processed,
+    import_validator_detail, write_avg_file,
+};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct Params {
+    #[structopt(
+        long = "db-path",
+        help = "Path to the sqlite scores history",
+        default_value = "db/score-sqlite3.db"
+    )]
+    db_path: String,
+
+    #[structopt(subcommand)]
+    command: HistoryCommand,
+}
+
+#[derive(Debug, StructOpt)]
+enum HistoryCommand {
+    /// Import a validator-detail CSV from score-past-epoch into the scores history
+    Import {
+        #[structopt(
+            long = "validator-detail-file",
+            help = "CSV written by score-past-epoch"
+        )]
+        validator_detail_file: String,
+    },
+    /// Import the post-processed scores CSV from score-post-process into the history
+    ImportPostProcessed {
+        #[structopt(
+            long = "post-processed-file",
+            help = "CSV written by score-post-process"
+        )]
+        post_processed_file: String,
+    },
+    /// Write the post-processed scores kept in the history as a post-processed scores CSV
+    ExportPostProcessed {
+        #[structopt(
+            long = "epoch",
+            help = "Epoch to export, defaults to the latest imported"
+        )]
+        epoch: Option<u64>,
+
+        #[structopt(long = "result-file", help = "Path to the output CSV file")]
+        result_file: String,
+    },
+    /// Add the columns missing in a post-processed history created by an older version
+    MigratePostProcessed,
+    /// Write the CSV with averaged scores consumed by score-post-process
+    CreateAvgFile {
+        #[structopt(
+            long = "epoch",
+            help = "Epoch to average, defaults to the latest imported"
+        )]
+        epoch: Option<u64>,
+
+        #[structopt(long = "result-file", help = "Path to the output CSV file")]
+        result_file: String,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let params = Params::from_args();
+
+    solana_logger::setup_with("info");
+
+    let mut db = ScoresDb::open(&params.db_path)?;
+
+    match params.command {
+        HistoryCommand::Import {
+            validator_detail_file,
+        } => {
+            let epoch = import_validator_detail(&mut db, &validator_detail_file)?;
+            info!("Epoch {} imported", epoch);
+        }
+        HistoryCommand::ImportPostProcessed {
+            post_processed_file,
+        } => {
+            let epoch = import_post_processed(&mut db, &post_processed_file)?;
+            info!("Epoch {} post-processed scores imported", epoch);
+        }
+        HistoryCommand::ExportPostProcessed { epoch, result_file } => {
+            let epoch = export_post_processed(&db, epoch, &result_file)?;
+            info!("Epoch {} post-processed scores exported", epoch);
+        }
+        HistoryCommand::MigratePostProcessed => {
+            let added_columns = db.migrate_post_processed()?;
+            if added_columns.is_empty() {
+                info!("The post-processed history is up to date");
+            }
+        }
+        HistoryCommand::CreateAvgFile { epoch, result_file } => {
+            let avg = create_avg(&db, epoch)?;
+            write_avg_file(&avg, &result_file)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+/// A row of the `scores` history table
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreRecord {
+    pub epoch: u64,
+    pub keybase_id: String,
+    pub name: String,
+    pub identity: String,
+    pub vote_address: String,
+    pub score: u64,
+    /// recomputed from adj_credits, `None` if no validator reached the threshold used for the average
+    pub avg_position: Option<f64>,
+    pub commission: u8,
+    pub active_stake: f64,
+    pub epoch_credits: u64,
+    pub data_center_concentration: f64,
+    pub data_center_asn: u64,
+    pub data_center_location: String,
+    pub can_halt_the_network_group: bool,
+    pub stake_state: String,
+    pub stake_state_reason: String,
+    pub www_url: String,
+    pub version: String,
+    /// share of the epoch total score
+    pub pct: f64,
+    /// share of the epoch total active stake
+    pub stake_conc: f64,
+    /// epoch credits discounted by commission and data center concentration
+    pub adj_credits: i64,
+    /// max commission ever observed for the vote account
+    pub max_commission: u8,
+}
@@ -1,27 +1,27 @@
 use {
     crate::{
         // generic_stake_pool::ValidatorStakeState,
+        commission_history::{commission_flipping_reason, EpochCommissionSamples},
         config::*,
         data_center_info::{self, *},
+        geo_concentration::GeoConcentration,
+        operator_groups::{group_operators, OperatorGroup, OperatorInfo},
         rpc_client_utils::*,
+        scoring::ScoreBreakdown,
+        snapshot::ClusterSnapshot,
+        validators_app::ValidatorsAppScores,
+        validators_list,
     },
     log::*,
     serde::{Deserialize, Serialize},
-    solana_client::rpc_client::RpcClient,
+    solana_client::rpc_response::RpcLeaderSchedule,
     solana_sdk::{
-        account::from_account,
-        account_utils::StateMut,
         clock::{Epoch, Slot},
-        commitment_config::CommitmentConfig,
         native_token::*,
         pubkey::Pubkey,
-        slot_history::{self, SlotHistory},
-        stake::{self, state::StakeState},
-        stake_history::StakeHistory,
-        sysvar,
     },
-    solana_vote_program::vote_state::VoteState,
     std::{
+        collections::BTreeSet,
         collections::HashMap,
         collections::HashSet,
         error,
@@ -34,7 +34,6 @@ use {
 
 type BoxResult<T> = Result<T, Box<dyn error::Error>>;
 type ValidatorList = HashSet<Pubkey>;
-type IdentityToParticipant = HashMap<Pubkey, Pubkey>;
 
 #[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize)]
 pub enum ValidatorStakeState {
@@ -60,6 +59,9 @@ pub struct ByIdentityInfo {
     pub keybase_id: String,
     pub name: String,
     pub www_url: String,
+    /// absent in classifications made before the sub-scores were kept
+    #[serde(default)]
+    pub scores: ValidatorsAppScores,
 }
 
 #[derive(Default, Clone, Deserialize, Serialize)]
@@ -75,8 +77,32 @@ pub struct ScoreData {
     pub data_center_concentration: f64,
     pub data_center_location: String,
     pub data_center_asn: u64,
+    /// stake percent of the ASN, country, city and hosting provider of the data center, see
+    /// [crate::geo_concentration]. Absent in classifications made before they were computed.
+    #[serde(default)]
+    pub asn_concentration: f64,
+    #[serde(default)]
+    pub country_concentration: f64,
+    #[serde(default)]
+    pub city_concentration: f64,
+    #[serde(default)]
+    pub hosting_provider_concentration: f64,
     pub validators_app_info: ByIdentityInfo,
     pub version: String,
+    /// leader slots of the validator in the scored epoch
+    #[serde(default)]
+    pub leader_slots: u64,
+    /// blocks produced in those leader slots
+    #[serde(default)]
+    pub blocks_produced: u64,
+    /// commission the rewards of the scored epoch were paid with, from the inflation rewards
+    /// or else from the last commission sample of the epoch. `None` if unknown.
+    #[serde(default)]
+    pub reward_commission: Option<u8>,
+    /// average slots between the last slot voted and the slot the vote landed in, over the
+    /// blocks sampled from the scored epoch. `None` if not measured.
+    #[serde(default)]
+    pub avg_vote_latency: Option<f64>,
 }
 
 #[derive(Default, Clone, Deserialize, Serialize)]
@@ -90,6 +116,11 @@ pub struct ValidatorClassification {
     // added optional validator scoring data
     pub score_data: Option<ScoreData>,
 
+    // How the score was computed from score_data, absent in classifications made before
+    // the breakdown was recorded
+    #[serde(default)]
+    pub score_breakdown: Option<ScoreBreakdown>,
+
     // Summary of the action was taken this epoch to advance the validator's stake
     pub stake_action: Option<String>,
 
@@ -102,53 +133,49 @@ pub struct ValidatorClassification {
 
     // The validator was not funded this epoch and should be prioritized next epoch
     pub prioritize_funding_in_next_epoch: Option<bool>,
+
+    // The vote accounts of the identity besides `vote_address`, their stake is included in
+    // the active stake of the score data
+    #[serde(default)]
+    pub other_vote_accounts: Vec<VoteAccount>,
+
+    // Id of the group of the validators run by the same operator, see
+    // `EpochClassificationV1::operator_groups`
+    #[serde(default)]
+    pub operator_group: Option<u32>,
+}
+
+impl ValidatorClassification {
+    /// Score recorded in the breakdown, computed from the score data for the classifications
+    /// made before the breakdown was recorded
+    pub fn score(&self, config: &Config) -> Option<u64> {
+        self.score_breakdown
+            .as_ref()
+            .map(|score_breakdown| score_breakdown.score)
+            .or_else(|| {
+                self.score_data
+                    .as_ref()
+                    .map(|score_data| score_data.score(config))
+            })
+    }
 }
 
 impl ScoreData {
+    /// The commission was raised for the rewards and lowered back before the scoring
+    pub fn is_commission_flipping(&self) -> bool {
+        matches!(
+            self.reward_commission,
+            Some(reward_commission) if reward_commission > self.commission
+        )
+    }
+
     pub fn score(&self, config: &Config) -> u64 {
-        if self.score_discounts.can_halt_the_network_group
-            || self.active_stake < config.score_min_stake
-            || self.average_position < config.min_avg_position
-            // if config.min_avg_position=100 => everybody passes
-            // if config.min_avg_position=50 => only validators above avg pass
-            || self.commission > config.score_max_commission
-        {
-            0
-        } else {
-            // if data_center_concentration = 25%, lose all score,
-            // data_center_concentration = 10%, lose 40% (rounded)
-            let discount_because_data_center_concentration = (self.data_center_concentration
-                * config.score_concentration_point_discount as f64)
-                as u64;
-
-            // score discounts according to commission
-            // apply commission % as a discount to credits_observed.
-            // The rationale es:
-            // If you're the top performer validator and get 300K credits, but you have 50% commission,
-            // from our user's point of view, it's the same as a 150K credits validator with 0% commission,
-            // both represent the same APY for the user.
-            // So to treat both the same we apply commission to self.epoch_credits
-            let discount_because_commission = self.commission as u64 * self.epoch_credits / 100;
-
-            // give extra score to above average validators in order to increase APY for our users
-            let points_added_above_average: u64 = if self.average_position > 50.0 {
-                let above = self.average_position - 50.0;
-                let multiplier = if above * above > 25.0 {
-                    25.0
-                } else {
-                    above * above
-                };
-                (multiplier * self.epoch_credits as f64) as u64
-            } else {
-                0
-            };
+        self.score_breakdown(config).score
+    }
 
-            //result
-            self.epoch_credits
-                .saturating_sub(discount_because_commission)
-                .saturating_sub(discount_because_data_center_concentration)
-                .saturating_add(points_added_above_average)
-        }
+    /// Score computed by the scoring model selected in the config, component by component
+    pub fn score_breakdown(&self, config: &Config) -> ScoreBreakdown {
+        config.scoring_model.score(self, config)
     }
 }
 
@@ -165,6 +192,93 @@ pub struct EpochClassificationV1 {
 
     // Informational notes regarding this epoch
     pub notes: Vec<String>,
+
+    // The effective config the epoch was classified with, absent in classifications made
+    // before the config was recorded
+    #[serde(default)]
+    pub config: Option<serde_yaml::Value>,
+
+    // Validators linked to the same operator, absent in classifications made before the
+    // operators were grouped
+    #[serde(default)]
+    pub operator_groups: Vec<OperatorGroup>,
+}
+
+/// Change of the classification of a validator between two revisions, `None` when the
+/// validator is not classified in the revision
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct ValidatorClassificationChange {
+    pub identity: String,
+    pub old_stake_state: Option<ValidatorStakeState>,
+    pub new_stake_state: Option<ValidatorStakeState>,
+    pub old_score: Option<u64>,
+    pub new_score: Option<u64>,
+}
+
+/// Validators whose stake state or score changed when rescoring an epoch
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ClassificationDiff {
+    pub epoch: Epoch,
+    pub old_revision: u32,
+    pub new_revision: u32,
+    pub changes: Vec<ValidatorClassificationChange>,
+}
+
+impl ClassificationDiff {
+    pub fn new(
+        epoch: Epoch,
+        (old_revision, old): (u32, &EpochClassificationV1),
+        (new_revision, new): (u32, &EpochClassificationV1),
+        config: &Config,
+    ) -> Self {
+        let empty = ValidatorClassificationByIdentity::new();
+        let old = old.validator_classifications.as_ref().unwrap_or(&empty);
+        let new = new.validator_classifications.as_ref().unwrap_or(&empty);
+        let identities: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+
+        let changes = identities
+            .into_iter()
+            .map(|identity| {
+                let old = old.get(identity);
+                let new = new.get(identity);
+                ValidatorClassificationChange {
+                    identity: identity.to_string(),
+                    old_stake_state: old.map(|classification| classification.stake_state),
+                    new_stake_state: new.map(|classification| classification.stake_state),
+                    old_score: old.and_then(|classification| classification.score(config)),
+                    new_score: new.and_then(|classification| classification.score(config)),
+                }
+            })
+            .filter(|change| {
+                change.old_stake_state != change.new_stake_state
+                    || change.old_score != change.new_score
+            })
+            .collect();
+
+        Self {
+            epoch,
+            old_revision,
+            new_revision,
+            changes,
+        }
+    }
+
+    /// Saves the diff as `epoch-N.rR.diff.yml` next to the new revision
+    pub fn save<P>(&self, path: P) -> Result<(), io::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let serialized = serde_yaml::to_string(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))?;
+
+        let mut file = File::create(path.as_ref().join(format!(
+            "epoch-{}.r{}.diff.yml",
+            self.epoch, self.new_revision
+        )))?;
+        file.write_all(&serialized.into_bytes())?;
+
+        Ok(())
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -196,6 +310,19 @@ impl EpochClassification {
         path.as_ref().join(format!("epoch-{}.yml", epoch))
     }
 
+    /// Revision 0 is the first classification of the epoch, a rescore saves `epoch-N.rR.yml`
+    fn revision_file_name<P>(epoch: Epoch, revision: u32, path: P) -> PathBuf
+    where
+        P: AsRef<Path>,
+    {
+        if revision == 0 {
+            Self::file_name(epoch, path)
+        } else {
+            path.as_ref()
+                .join(format!("epoch-{}.r{}.yml", epoch, revision))
+        }
+    }
+
     pub fn exists<P>(epoch: Epoch, path: P) -> bool
     where
         P: AsRef<Path>,
@@ -203,16 +330,46 @@ impl EpochClassification {
         Self::file_name(epoch, path).exists()
     }
 
+    /// Latest revision of the classification of `epoch`, `None` if the epoch is not classified
+    pub fn latest_revision<P>(epoch: Epoch, path: P) -> Option<u32>
+    where
+        P: AsRef<Path>,
+    {
+        if !Self::exists(epoch, &path) {
+            return None;
+        }
+        (1..)
+            .take_while(|revision| Self::revision_file_name(epoch, *revision, &path).exists())
+            .last()
+            .or(Some(0))
+    }
+
+    /// Loads the latest revision of the classification of `epoch`
     pub fn load<P>(epoch: Epoch, path: P) -> Result<Self, io::Error>
     where
         P: AsRef<Path>,
     {
-        let file = File::open(Self::file_name(epoch, path))?;
+        let revision = Self::latest_revision(epoch, &path).unwrap_or(0);
+        Self::load_revision(epoch, revision, path)
+    }
+
+    pub fn load_revision<P>(epoch: Epoch, revision: u32, path: P) -> Result<Self, io::Error>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(Self::revision_file_name(epoch, revision, path))?;
         serde_yaml::from_reader(file)
             .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))
     }
 
     pub fn save<P>(&self, epoch: Epoch, path: P) -> Result<(), io::Error>
+    where
+        P: AsRef<Path>,
+    {
+        self.save_revision(epoch, 0, path)
+    }
+
+    pub fn save_revision<P>(&self, epoch: Epoch, revision: u32, path: P) -> Result<(), io::Error>
     where
         P: AsRef<Path>,
     {
@@ -220,93 +377,13 @@ impl EpochClassification {
             .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))?;
 
         fs::create_dir_all(&path)?;
-        let mut file = File::create(Self::file_name(epoch, path))?;
+        let mut file = File::create(Self::revision_file_name(epoch, revision, path))?;
         file.write_all(&serialized.into_bytes())?;
 
         Ok(())
     }
 }
 
-fn get_self_stake_by_vote_account(
-    rpc_client: &RpcClient,
-    epoch: Epoch,
-    vote_account_info: &[VoteAccountInfo],
-) -> BoxResult<HashMap<Pubkey, u64>> {
-    let mut self_stake_by_vote_account = HashMap::new();
-
-    info!("Building list of authorized voters...");
-
-    let mut authorized_withdrawer = HashMap::new();
-    for VoteAccountInfo { vote_address, .. } in vote_account_info {
-        let vote_account = rpc_client.get_account(vote_address)?;
-
-        if let Some(vote_state) = VoteState::from(&vote_account) {
-            authorized_withdrawer.insert(vote_address, vote_state.authorized_withdrawer);
-        }
-    }
-
-    info!("Fetching stake accounts...");
-    let all_stake_accounts = rpc_client.get_program_accounts(&stake::program::id())?;
-    info!("{} stake accounts", all_stake_accounts.len());
-
-    let stake_history_account = rpc_client
-        .get_account_with_commitment(&sysvar::stake_history::id(), CommitmentConfig::finalized())?
-        .value
-        .unwrap();
-
-    let stake_history: StakeHistory =
-        from_account(&stake_history_account).ok_or("Failed to deserialize stake history")?;
-
-    for (_stake_pubkey, stake_account) in all_stake_accounts {
-        if let Ok(StakeState::Stake(meta, stake)) = stake_account.state() {
-            let vote_address = &stake.delegation.voter_pubkey;
-            if let Some(vote_account_authorized_withdrawer) =
-                authorized_withdrawer.get(vote_address)
-            {
-                if *vote_account_authorized_withdrawer == meta.authorized.withdrawer {
-                    let effective_stake = stake
-                        .delegation
-                        .stake_activating_and_deactivating(epoch, Some(&stake_history))
-                        .0;
-                    if effective_stake > 0 {
-                        *self_stake_by_vote_account.entry(*vote_address).or_default() +=
-                            effective_stake;
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(self_stake_by_vote_account)
-}
-
-fn get_confirmed_blocks(
-    rpc_client: &RpcClient,
-    start_slot: Slot,
-    end_slot: Slot,
-) -> BoxResult<HashSet<Slot>> {
-    info!(
-        "loading slot history. slot range is [{},{}]",
-        start_slot, end_slot
-    );
-    let slot_history_account = rpc_client
-        .get_account_with_commitment(&sysvar::slot_history::id(), CommitmentConfig::finalized())?
-        .value
-        .unwrap();
-
-    let slot_history: SlotHistory =
-        from_account(&slot_history_account).ok_or("Failed to deserialize slot history")?;
-
-    if start_slot >= slot_history.oldest() && end_slot <= slot_history.newest() {
-        info!("slot range within the SlotHistory sysvar");
-        Ok((start_slot..=end_slot)
-            .filter(|slot| slot_history.check(*slot) == slot_history::Check::Found)
-            .collect())
-    } else {
-        Err("slot range is not within the SlotHistory sysvar".into())
-    }
-}
-
 type ClassifyResult = (
     // quality
     ValidatorList,
@@ -314,6 +391,8 @@ type ClassifyResult = (
     ValidatorList,
     // classification reason
     HashMap<Pubkey, String>,
+    // blocks produced and leader slots
+    HashMap<Pubkey, (usize, usize)>,
     // cluster_skip_rate
     usize,
     // too_many_poor_block_producers
@@ -323,7 +402,7 @@ type ClassifyResult = (
 fn classify_producers(
     first_slot_in_epoch: Slot,
     confirmed_blocks: HashSet<u64>,
-    leader_schedule: HashMap<String, Vec<usize>>,
+    leader_schedule: &RpcLeaderSchedule,
     config: &Config,
 ) -> BoxResult<ClassifyResult> {
     let mut poor_block_producers = HashSet::new();
@@ -337,7 +416,7 @@ fn classify_producers(
         let mut validator_blocks = 0;
         let mut validator_slots = 0;
         for relative_slot in relative_slots {
-            let slot = first_slot_in_epoch + relative_slot as Slot;
+            let slot = first_slot_in_epoch + *relative_slot as Slot;
             total_slots += 1;
             validator_slots += 1;
             if confirmed_blocks.contains(&slot) {
@@ -346,14 +425,14 @@ fn classify_producers(
             }
         }
         if validator_slots > 0 {
-            let validator_identity = Pubkey::from_str(&validator_identity)?;
+            let validator_identity = Pubkey::from_str(validator_identity)?;
             let e = blocks_and_slots.entry(validator_identity).or_insert((0, 0));
             e.0 += validator_blocks;
             e.1 += validator_slots;
         }
     }
     let cluster_average_skip_rate = 100 - total_blocks * 100 / total_slots;
-    for (validator_identity, (blocks, slots)) in blocks_and_slots {
+    for (&validator_identity, &(blocks, slots)) in &blocks_and_slots {
         let skip_rate: usize = 100 - (blocks * 100 / slots);
 
         let msg = format!(
@@ -391,35 +470,21 @@ fn classify_producers(
         quality_block_producers,
         poor_block_producers,
         reason_msg,
+        blocks_and_slots,
         cluster_average_skip_rate,
         too_many_poor_block_producers,
     ))
 }
 
-/// Split validators into quality/poor lists based on their block production over the given `epoch`
+/// Split validators into quality/poor lists based on their block production over the scored epoch
 fn classify_block_producers(
-    rpc_client: &RpcClient,
+    snapshot: &ClusterSnapshot,
     config: &Config,
-    epoch: Epoch,
 ) -> BoxResult<ClassifyResult> {
-    let epoch_schedule = rpc_client.get_epoch_schedule()?;
-    let first_slot_in_epoch = epoch_schedule.get_first_slot_in_epoch(epoch);
-    let last_slot_in_epoch = epoch_schedule.get_last_slot_in_epoch(epoch);
-
-    let confirmed_blocks =
-        get_confirmed_blocks(rpc_client, first_slot_in_epoch, last_slot_in_epoch)?;
-
-    let leader_schedule = rpc_client
-        .get_leader_schedule_with_commitment(
-            Some(first_slot_in_epoch),
-            CommitmentConfig::finalized(),
-        )?
-        .unwrap();
-
     classify_producers(
-        first_slot_in_epoch,
-        confirmed_blocks,
-        leader_schedule,
+        snapshot.first_slot_of_scored_epoch(),
+        snapshot.confirmed_blocks(),
+        &snapshot.leader_schedule,
         config,
     )
 }
@@ -471,42 +536,59 @@ fn classify_poor_voters(
     )
 }
 
-pub fn classify(
-    rpc_client: &RpcClient,
-    config: &Config,
-    epoch: Epoch,
-    validator_list: &ValidatorList,
-    identity_to_participant: &IdentityToParticipant,
-) -> BoxResult<EpochClassificationV1> {
-    let last_epoch = epoch - 1;
-
-    let data_centers = match data_center_info::get(&config.cluster.to_string()) {
-        Ok(data_centers) => {
-            // Sanity check the infrastructure stake percent data.  More than 35% indicates there's
-            // probably a bug in the data source. Abort if so.
-            let max_infrastucture_stake_percent = data_centers
-                .info
-                .iter()
-                .map(|dci| dci.stake_percent.round() as usize)
-                .max()
-                .unwrap_or(100);
+/// Classifies the validators from the cluster state captured in the snapshot, no network
+/// access happens here so a snapshot is always classified the same way
+pub fn classify(snapshot: &ClusterSnapshot, config: &Config) -> BoxResult<EpochClassificationV1> {
+    if snapshot.cluster != config.cluster.to_string() {
+        return Err(format!(
+            "Snapshot of {} cannot be classified as {}",
+            snapshot.cluster, config.cluster
+        )
+        .into());
+    }
+    let last_epoch = snapshot.epoch - 1;
 
-            info!(
-                "Largest data center stake concentration: ~{}%",
-                max_infrastucture_stake_percent
-            );
-            if max_infrastucture_stake_percent as f64 > config.max_largest_dc_stake_percent {
-                return Err("Largest data center stake concentration is too high".into());
-            }
-            data_centers
-        }
-        Err(err) => {
-            if config.max_infrastructure_concentration.is_some() {
-                return Err(err);
-            }
-            panic!("infrastructure concentration calc. failed: {}", err);
-        }
+    let (validator_list, identity_to_participant): (ValidatorList, _) = match config.cluster {
+        Cluster::MainnetBeta => (
+            snapshot
+                .mainnet_identity_to_participant
+                .keys()
+                .cloned()
+                .collect(),
+            &snapshot.mainnet_identity_to_participant,
+        ),
+        Cluster::Testnet | Cluster::Devnet => (
+            validators_list::testnet_validators().into_iter().collect(),
+            &snapshot.testnet_identity_to_participant,
+        ),
+    };
+
+    let data_centers = match &snapshot.gossip_data_centers {
+        Some(gossip_data_centers) => data_center_info::from_gossip(
+            gossip_data_centers,
+            &snapshot.vote_accounts,
+            snapshot.validators_app.as_ref(),
+        ),
+        None => data_center_info::from_validators_app(snapshot.validators_app.as_ref()),
     };
+    let hosting_providers = snapshot.hosting_providers.clone().unwrap_or_default();
+    let geo_concentration = GeoConcentration::new(&data_centers.info, &hosting_providers);
+    // Sanity check the infrastructure stake percent data.  More than 35% indicates there's
+    // probably a bug in the data source. Abort if so.
+    let max_infrastucture_stake_percent = data_centers
+        .info
+        .iter()
+        .map(|dci| dci.stake_percent.round() as usize)
+        .max()
+        .unwrap_or(100);
+
+    info!(
+        "Largest data center stake concentration: ~{}%",
+        max_infrastucture_stake_percent
+    );
+    if max_infrastucture_stake_percent as f64 > config.max_largest_dc_stake_percent {
+        return Err("Largest data center stake concentration is too high".into());
+    }
 
     let infrastructure_concentration_too_high = data_centers
         .info
@@ -524,7 +606,7 @@ pub fn classify(
         .collect::<HashMap<_, _>>();
 
     let (mut vote_account_info, total_active_stake) =
-        get_vote_account_info(rpc_client, last_epoch)?;
+        get_vote_account_info(&snapshot.vote_accounts, last_epoch);
 
     // compute cumulative_stake_limit => active_stake of the last validator inside the can-halt-the-network group
     // we later set score=0 to all validators whose stake >= concentrated_validators_stake_limit
@@ -550,21 +632,33 @@ pub fn classify(
         lamports_to_sol(last_under_nakamoto_active_stake),
     );
 
-    // Note: get_self_stake_by_vote_account is expensive because it does a RPC call for each validator
-    // we skip this data gathering if config.min_self_stake_lamports==0
-    let self_stake_by_vote_account = if config.min_self_stake_lamports > 0 {
-        get_self_stake_by_vote_account(rpc_client, epoch, &vote_account_info)?
-    } else {
-        HashMap::new()
+    // the self stake is only captured if config.min_self_stake_lamports > 0
+    let self_stake_by_vote_account = match &snapshot.self_stake_by_vote_account {
+        Some(self_stake_by_vote_account) => self_stake_by_vote_account.clone(),
+        None if config.min_self_stake_lamports > 0 => {
+            return Err("The snapshot does not contain the self stake of the validators".into())
+        }
+        None => HashMap::new(),
     };
 
-    let cluster_nodes_versions: HashMap<String, _> = rpc_client
-        .get_cluster_nodes()?
-        .into_iter()
+    // the inflation rewards tell the commission the rewards were actually paid with, the
+    // samples of the epoch are the fallback when the RPC node does not serve them
+    let mut reward_commissions = EpochCommissionSamples {
+        epoch: last_epoch,
+        samples: snapshot.commission_samples.clone(),
+    }
+    .last_commissions();
+    if let Some(inflation_reward_commissions) = &snapshot.reward_commissions {
+        reward_commissions.extend(inflation_reward_commissions.clone());
+    }
+
+    let cluster_nodes_versions: HashMap<String, _> = snapshot
+        .cluster_nodes
+        .iter()
         .filter_map(|rpc_contact_info| {
             if let Some(ref version) = rpc_contact_info.version {
                 if let Ok(semver) = semver::Version::parse(version) {
-                    return Some((rpc_contact_info.pubkey, semver));
+                    return Some((rpc_contact_info.pubkey.clone(), semver));
                 }
             }
             None
@@ -574,16 +668,16 @@ pub fn classify(
     let (cluster_nodes_with_old_version, min_release_version): (HashMap<String, _>, _) =
         match config.min_release_version {
             Some(ref min_release_version) => (
-                rpc_client
-                    .get_cluster_nodes()?
-                    .into_iter()
+                snapshot
+                    .cluster_nodes
+                    .iter()
                     .filter_map(|rpc_contact_info| {
                         if let Ok(identity) = Pubkey::from_str(&rpc_contact_info.pubkey) {
                             if config.score_all || validator_list.contains(&identity) {
                                 if let Some(ref version) = rpc_contact_info.version {
                                     if let Ok(semver) = semver::Version::parse(version) {
                                         if semver < *min_release_version {
-                                            return Some((rpc_contact_info.pubkey, semver));
+                                            return Some((rpc_contact_info.pubkey.clone(), semver));
                                         }
                                     }
                                 }
@@ -608,9 +702,10 @@ pub fn classify(
         quality_block_producers,
         poor_block_producers,
         block_producer_classification_reason,
+        blocks_and_slots,
         cluster_average_skip_rate,
         too_many_poor_block_producers,
-    ) = classify_block_producers(rpc_client, config, last_epoch)?;
+    ) = classify_block_producers(snapshot, config)?;
 
     let not_in_leader_schedule: ValidatorList = validator_list
         .difference(
@@ -652,6 +747,7 @@ pub fn classify(
         ),
         format!("Solana release {} or greater required", min_release_version),
         format!("Maximum commission: {}%", config.max_commission),
+        format!("Scoring model: {}", config.scoring_model.name()),
         format!(
             "Minimum required self stake: {}",
             Sol(config.min_self_stake_lamports)
@@ -661,12 +757,33 @@ pub fn classify(
             Sol(config.max_active_stake_lamports)
         ),
     ];
+    if let Some(captured_in_epoch) = snapshot.captured_in_epoch {
+        notes.push(format!(
+            "Epoch {} scored in epoch {}: stakes, commissions, releases and data centers are the ones of epoch {}",
+            last_epoch, captured_in_epoch, captured_in_epoch
+        ));
+    }
+    if let Some(vote_latencies) = &snapshot.vote_latencies {
+        let votes: u64 = vote_latencies.values().map(|latency| latency.votes).sum();
+        let total_latency: u64 = vote_latencies
+            .values()
+            .map(|latency| latency.total_latency)
+            .sum();
+        notes.push(format!(
+            "Average vote latency in epoch {}: {:.2} slots ({} votes in {} sampled blocks)",
+            last_epoch,
+            total_latency as f64 / votes.max(1) as f64,
+            votes,
+            config.vote_latency_sample_blocks
+        ));
+    }
     if let Some(max_infrastructure_concentration) = config.max_infrastructure_concentration {
         notes.push(format!(
             "Maximum infrastructure concentration: {:0}%",
             max_infrastructure_concentration
         ));
     }
+    notes.extend(geo_concentration.notes());
 
     if cluster_average_skip_rate > config.bad_cluster_average_skip_rate {
         notes.push("Cluster average skip rate is poor".to_string());
@@ -691,6 +808,7 @@ pub fn classify(
         ));
     }
 
+    let mut operator_groups = vec![];
     let validator_classifications = if too_many_poor_voters
         || too_many_old_validators
         || too_many_poor_block_producers
@@ -700,6 +818,9 @@ pub fn classify(
     } else {
         let mut validator_classifications = HashMap::new();
         let mut total_skipped: u32 = 0;
+        let mut commission_flipping: u32 = 0;
+        let mut multiple_vote_accounts: u32 = 0;
+        let mut operators = vec![];
 
         for VoteAccountInfo {
             identity,
@@ -707,6 +828,7 @@ pub fn classify(
             commission,
             active_stake,
             epoch_credits,
+            other_vote_accounts,
         } in vote_account_info
         {
             if !config.score_all && !validator_list.contains(&identity) {
@@ -726,6 +848,33 @@ pub fn classify(
 
             let current_data_center = validators_app_info.data_center_id.clone();
 
+            operators.push(OperatorInfo {
+                identity,
+                withdraw_authorities: std::iter::once(&vote_address)
+                    .chain(
+                        other_vote_accounts
+                            .iter()
+                            .map(|vote_account| &vote_account.vote_address),
+                    )
+                    .filter_map(|vote_address| {
+                        snapshot
+                            .vote_withdrawers
+                            .as_ref()
+                            .and_then(|vote_withdrawers| vote_withdrawers.get(vote_address))
+                            .cloned()
+                    })
+                    .collect(),
+                identity_funder: snapshot
+                    .identity_funders
+                    .as_ref()
+                    .and_then(|identity_funders| identity_funders.get(&identity))
+                    .cloned(),
+                keybase_id: validators_app_info.keybase_id.clone(),
+                www_url: validators_app_info.www_url.clone(),
+                name: validators_app_info.name.clone(),
+                data_center_id: current_data_center.clone(),
+            });
+
             // score: check data center concentration
             let data_center_info = data_centers
                 .info
@@ -733,10 +882,17 @@ pub fn classify(
                 .find(|x| x.id == current_data_center)
                 .unwrap();
 
-            let self_stake = self_stake_by_vote_account
-                .get(&vote_address)
-                .cloned()
-                .unwrap_or_default();
+            let self_stake = std::iter::once(&vote_address)
+                .chain(
+                    other_vote_accounts
+                        .iter()
+                        .map(|vote_account| &vote_account.vote_address),
+                )
+                .filter_map(|vote_address| self_stake_by_vote_account.get(vote_address))
+                .sum::<u64>();
+            if !other_vote_accounts.is_empty() {
+                multiple_vote_accounts += 1;
+            }
 
             let block_producer_classification_reason_msg = block_producer_classification_reason
                 .get(&identity)
@@ -744,11 +900,20 @@ pub fn classify(
                 .unwrap_or_default();
             let vote_credits_msg =
                 format!("{} credits earned in epoch {}", epoch_credits, last_epoch);
+            let (blocks_produced, leader_slots) =
+                blocks_and_slots.get(&identity).cloned().unwrap_or_default();
 
             // no score if in the can-halt-the-network group
             score_discounts.can_halt_the_network_group =
                 active_stake >= last_under_nakamoto_active_stake;
 
+            let reward_commission = reward_commissions.get(&vote_address).cloned();
+            let avg_vote_latency = snapshot
+                .vote_latencies
+                .as_ref()
+                .and_then(|vote_latencies| vote_latencies.get(&vote_address))
+                .and_then(|vote_latency| vote_latency.average());
+
             let (stake_state, reason) = if let Some(concentration) =
                 infrastructure_concentration_too_high.get(&identity)
             {
@@ -774,6 +939,11 @@ pub fn classify(
                     ValidatorStakeState::None,
                     format!("Commission is too high: {}% commission", commission),
                 )
+            } else if let Some(reason) =
+                commission_flipping_reason(last_epoch, commission, reward_commission)
+            {
+                commission_flipping += 1;
+                (ValidatorStakeState::None, reason)
             } else if poor_voters.contains(&identity) {
                 (
                     ValidatorStakeState::None,
@@ -825,39 +995,79 @@ pub fn classify(
                 Sol(self_stake),
             );
 
+            let geo_shares = geo_concentration.shares(&data_center_info.id, &hosting_providers);
+            let score_data = ScoreData {
+                epoch_credits,
+                average_position: epoch_credits as f64 / avg_epoch_credits as f64 * 50.0,
+                score_discounts,
+                commission,
+                active_stake,
+                data_center_concentration: data_center_info.stake_percent,
+                data_center_asn: data_center_info.id.asn,
+                data_center_location: data_center_info.id.location.clone(),
+                asn_concentration: geo_shares.asn,
+                country_concentration: geo_shares.country,
+                city_concentration: geo_shares.city,
+                hosting_provider_concentration: geo_shares.hosting_provider,
+                validators_app_info,
+                version: cluster_nodes_versions
+                    .get(&identity.to_string())
+                    .unwrap_or(&semver::Version::parse("0.0.0")?)
+                    .to_string(),
+                leader_slots: leader_slots as u64,
+                blocks_produced: blocks_produced as u64,
+                reward_commission,
+                avg_vote_latency,
+            };
+            let score_breakdown = score_data.score_breakdown(config);
+
             validator_classifications.insert(
                 identity,
                 ValidatorClassification {
                     identity,
                     vote_address,
                     stake_state,
-                    score_data: Some(ScoreData {
-                        epoch_credits,
-                        average_position: epoch_credits as f64 / avg_epoch_credits as f64 * 50.0,
-                        score_discounts,
-                        commission,
-                        active_stake,
-                        data_center_concentration: data_center_info.stake_percent,
-                        data_center_asn: data_center_info.id.asn,
-                        data_center_location: data_center_info.id.location.clone(),
-                        validators_app_info,
-                        version: cluster_nodes_versions
-                            .get(&identity.to_string())
-                            .unwrap_or(&semver::Version::parse("0.0.0")?)
-                            .to_string(),
-                    }),
+                    score_data: Some(score_data),
+                    score_breakdown: Some(score_breakdown),
                     stake_action: None,
                     stake_state_reason: reason,
                     current_data_center: Some(current_data_center.clone()),
                     participant,
                     prioritize_funding_in_next_epoch: None,
+                    other_vote_accounts,
+                    operator_group: None,
                 },
             );
         }
+
+        operator_groups = group_operators(&operators);
+        for operator_group in &operator_groups {
+            for identity in &operator_group.identities {
+                if let Some(classification) = validator_classifications.get_mut(identity) {
+                    classification.operator_group = Some(operator_group.id);
+                }
+            }
+        }
         notes.push(format!(
             "{} validators processed",
             validator_classifications.len()
         ));
+        notes.push(format!(
+            "{} validators with a commission at reward time above the one at scoring time",
+            commission_flipping
+        ));
+        notes.push(format!(
+            "{} validators with multiple vote accounts, scored with the vote account that voted most recently and the stake of all of them",
+            multiple_vote_accounts
+        ));
+        notes.push(format!(
+            "{} operators running multiple validators, {} validators in total",
+            operator_groups.len(),
+            operator_groups
+                .iter()
+                .map(|operator_group| operator_group.identities.len())
+                .sum::<usize>()
+        ));
         info!(
             "{} validators, {} skipped",
             &validator_classifications.len(),
@@ -874,27 +1084,307 @@ pub fn classify(
         data_center_info: data_centers.info,
         validator_classifications,
         notes,
+        config: Some(serde_yaml::to_value(config)?),
+        operator_groups,
     })
 }
 
 #[cfg(test)]
 mod test {
-    use super::*;
+    use {
+        super::*,
+        crate::{commission_history::CommissionSample, data_source::InMemoryDataSource},
+        solana_client::rpc_response::{RpcInflationReward, RpcVoteAccountInfo},
+        solana_sdk::{
+            account::Account, epoch_schedule::EpochSchedule, slot_history::SlotHistory, sysvar,
+        },
+    };
+
+    /// Four validators with 8 leader slots each in epoch 1, the last one skipping all its
+    /// slots and earning few credits, the first one holding most of the stake
+    fn synthetic_cluster() -> (InMemoryDataSource, Vec<Pubkey>) {
+        let epoch_schedule = EpochSchedule::custom(32, 32, false);
+        let mut data_source = InMemoryDataSource::new(2, epoch_schedule);
+        let identities: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+
+        let mut leader_schedule = RpcLeaderSchedule::new();
+        for (i, (identity, (active_stake, credits))) in identities
+            .iter()
+            .zip([(4, 1_000), (1, 1_000), (1, 1_000), (1, 200)])
+            .enumerate()
+        {
+            leader_schedule.insert(identity.to_string(), (i * 8..(i + 1) * 8).collect());
+            data_source.vote_accounts.current.push(RpcVoteAccountInfo {
+                vote_pubkey: Pubkey::new_unique().to_string(),
+                node_pubkey: identity.to_string(),
+                activated_stake: active_stake * sol_to_lamports(1_000_000.0),
+                commission: 5,
+                epoch_vote_account: true,
+                epoch_credits: vec![(1, 5_000 + credits, 5_000)],
+                last_vote: 70,
+                root_slot: 60,
+            });
+        }
+        data_source.leader_schedules.insert(1, leader_schedule);
+
+        // the slots 56..=63 of the last validator are skipped
+        let mut slot_history = SlotHistory::default();
+        for slot in (1..56).chain(64..=70) {
+            slot_history.add(slot);
+        }
+        data_source.accounts.insert(
+            sysvar::slot_history::id(),
+            Account::new_data(1, &slot_history, &sysvar::id()).unwrap(),
+        );
+
+        (data_source, identities)
+    }
+
+    /// Scores all the validators of [synthetic_cluster], too few to skip the stake adjustments
+    fn synthetic_config() -> Config {
+        Config {
+            cluster: Cluster::Devnet,
+            score_all: true,
+            max_poor_voter_percentage: 100,
+            max_poor_block_producer_percentage: 100,
+            ..Config::default_for_test()
+        }
+    }
+
+    #[test]
+    fn test_classify_synthetic_cluster() {
+        let config = synthetic_config();
+        let (data_source, identities) = synthetic_cluster();
+
+        let snapshot = ClusterSnapshot::capture(&data_source, &config, 2).unwrap();
+        assert_eq!(snapshot.skipped_slots, (56..=63).collect::<Vec<_>>());
+
+        let validator_classifications = classify(&snapshot, &config)
+            .unwrap()
+            .validator_classifications
+            .unwrap();
+        assert_eq!(validator_classifications.len(), 4);
+
+        let classification = |identity: &Pubkey| validator_classifications.get(identity).unwrap();
+        let score_data = |identity: &Pubkey| classification(identity).score_data.as_ref().unwrap();
+
+        // can halt the network
+        assert_eq!(
+            classification(&identities[0]).stake_state,
+            ValidatorStakeState::Bonus
+        );
+        assert_eq!(score_data(&identities[0]).score(&config), 0);
+
+        assert_eq!(
+            classification(&identities[1]).stake_state,
+            ValidatorStakeState::Bonus
+        );
+        assert_eq!(score_data(&identities[1]).leader_slots, 8);
+        assert_eq!(score_data(&identities[1]).blocks_produced, 8);
+        // 1_000 credits - 50 (5% commission) + 25 * 1_000 above average bonus
+        assert_eq!(score_data(&identities[1]).score(&config), 25_950);
+
+        // 200 credits, under 50% of the 800 average
+        assert_eq!(
+            classification(&identities[3]).stake_state,
+            ValidatorStakeState::None
+        );
+        assert_eq!(score_data(&identities[3]).blocks_produced, 0);
+    }
 
     #[test]
-    fn test_staked_for() {
-        let mut vc = ValidatorClassification::default();
-
-        assert!(!vc.staked_for(0, 0));
-        assert!(!vc.staked_for(1, 0));
-        assert!(!vc.staked_for(0, 1));
-
-        vc.stake_states = Some(vec![
-            (ValidatorStakeState::None, String::new()),
-            (ValidatorStakeState::Baseline, String::new()),
-            (ValidatorStakeState::Bonus, String::new()),
-        ]);
-        assert!(!vc.staked_for(3, 3));
-        assert!(vc.staked_for(2, 3));
+    fn test_classify_multiple_vote_accounts() {
+        let config = synthetic_config();
+        let (mut data_source, identities) = synthetic_cluster();
+        let vote_address: Pubkey = data_source.vote_accounts.current[2]
+            .vote_pubkey
+            .parse()
+            .unwrap();
+
+        // a second vote account of the third validator, which stopped voting
+        let other_vote_address = Pubkey::new_unique();
+        data_source
+            .vote_accounts
+            .delinquent
+            .push(RpcVoteAccountInfo {
+                vote_pubkey: other_vote_address.to_string(),
+                node_pubkey: identities[2].to_string(),
+                activated_stake: sol_to_lamports(2_000_000.0),
+                commission: 100,
+                epoch_vote_account: true,
+                epoch_credits: vec![(1, 5_100, 5_000)],
+                last_vote: 50,
+                root_slot: 40,
+            });
+
+        let snapshot = ClusterSnapshot::capture(&data_source, &config, 2).unwrap();
+        let validator_classifications = classify(&snapshot, &config)
+            .unwrap()
+            .validator_classifications
+            .unwrap();
+        assert_eq!(validator_classifications.len(), 4);
+
+        let classification = validator_classifications.get(&identities[2]).unwrap();
+        assert_eq!(classification.vote_address, vote_address);
+        assert_eq!(classification.other_vote_accounts.len(), 1);
+        assert_eq!(
+            classification.other_vote_accounts[0].vote_address,
+            other_vote_address
+        );
+        assert_eq!(classification.other_vote_accounts[0].epoch_credits, 100);
+
+        // the stake of both vote accounts, the commission and credits of the one voting
+        let score_data = classification.score_data.as_ref().unwrap();
+        assert_eq!(score_data.active_stake, sol_to_lamports(3_000_000.0));
+        assert_eq!(score_data.commission, 5);
+        assert_eq!(score_data.epoch_credits, 1_000);
+        assert!(validator_classifications
+            .get(&identities[1])
+            .unwrap()
+            .other_vote_accounts
+            .is_empty());
+    }
+
+    #[test]
+    fn test_classify_commission_flipping() {
+        let config = synthetic_config();
+        let (mut data_source, identities) = synthetic_cluster();
+        let vote_addresses: Vec<Pubkey> = data_source
+            .vote_accounts
+            .current
+            .iter()
+            .map(|info| info.vote_pubkey.parse().unwrap())
+            .collect();
+
+        // the rewards of epoch 1 were paid with a 100% commission, 5% when scored
+        data_source.inflation_rewards.insert(
+            1,
+            HashMap::from([(
+                vote_addresses[1],
+                RpcInflationReward {
+                    epoch: 1,
+                    effective_slot: 64,
+                    amount: 0,
+                    post_balance: 0,
+                    commission: Some(100),
+                },
+            )]),
+        );
+        let mut snapshot = ClusterSnapshot::capture(&data_source, &config, 2).unwrap();
+        assert_eq!(
+            snapshot.reward_commissions,
+            Some(HashMap::from([(vote_addresses[1], 100)]))
+        );
+        // no inflation reward, 100% in the last sample of epoch 1
+        snapshot.commission_samples = vec![
+            CommissionSample {
+                slot: 40,
+                commissions: HashMap::from([(vote_addresses[2], 5)]),
+            },
+            CommissionSample {
+                slot: 62,
+                commissions: HashMap::from([(vote_addresses[2], 100)]),
+            },
+        ];
+
+        let validator_classifications = classify(&snapshot, &config)
+            .unwrap()
+            .validator_classifications
+            .unwrap();
+        let classification = |identity: &Pubkey| validator_classifications.get(identity).unwrap();
+        let score_data = |identity: &Pubkey| classification(identity).score_data.as_ref().unwrap();
+
+        for identity in &identities[1..=2] {
+            assert_eq!(
+                classification(identity).stake_state,
+                ValidatorStakeState::None
+            );
+            assert_eq!(score_data(identity).reward_commission, Some(100));
+            assert!(score_data(identity).is_commission_flipping());
+        }
+        assert_eq!(score_data(&identities[0]).reward_commission, None);
+        assert!(!score_data(&identities[0]).is_commission_flipping());
+    }
+
+    #[test]
+    fn test_classification_diff() {
+        let config = Config::default_for_test();
+        let identities: Vec<_> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let classification = |stake_state, score| ValidatorClassification {
+            stake_state,
+            score_breakdown: Some(ScoreBreakdown {
+                score,
+                ..ScoreBreakdown::default()
+            }),
+            ..ValidatorClassification::default()
+        };
+        let old = EpochClassificationV1 {
+            validator_classifications: Some(
+                vec![
+                    (
+                        identities[0],
+                        classification(ValidatorStakeState::Bonus, 10),
+                    ),
+                    (
+                        identities[1],
+                        classification(ValidatorStakeState::Bonus, 10),
+                    ),
+                    (
+                        identities[2],
+                        classification(ValidatorStakeState::Baseline, 5),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            ..EpochClassificationV1::default()
+        };
+        let new = EpochClassificationV1 {
+            validator_classifications: Some(
+                vec![
+                    (
+                        identities[0],
+                        classification(ValidatorStakeState::Bonus, 10),
+                    ),
+                    (identities[1], classification(ValidatorStakeState::None, 0)),
+                    (
+                        identities[3],
+                        classification(ValidatorStakeState::Baseline, 7),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            ..EpochClassificationV1::default()
+        };
+
+        let diff = ClassificationDiff::new(7, (0, &old), (1, &new), &config);
+        let mut changes = diff.changes;
+        changes.sort_by(|a, b| a.identity.cmp(&b.identity));
+        let mut expected = vec![
+            ValidatorClassificationChange {
+                identity: identities[1].to_string(),
+                old_stake_state: Some(ValidatorStakeState::Bonus),
+                new_stake_state: Some(ValidatorStakeState::None),
+                old_score: Some(10),
+                new_score: Some(0),
+            },
+            ValidatorClassificationChange {
+                identity: identities[2].to_string(),
+                old_stake_state: Some(ValidatorStakeState::Baseline),
+                new_stake_state: None,
+                old_score: Some(5),
+                new_score: None,
+            },
+            ValidatorClassificationChange {
+                identity: identities[3].to_string(),
+                old_stake_state: None,
+                new_stake_state: Some(ValidatorStakeState::Baseline),
+                old_score: None,
+                new_score: Some(7),
+            },
+        ];
+        expected.sort_by(|a, b| a.identity.cmp(&b.identity));
+        assert_eq!(changes, expected);
     }
 }
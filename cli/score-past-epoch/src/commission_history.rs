@@ -0,0 +1,192 @@
+use {
+    crate::{config::Config, data_source::ClusterDataSource},
+    log::*,
+    serde::{Deserialize, Serialize},
+    solana_sdk::{
+        clock::{Epoch, Slot},
+        pubkey::Pubkey,
+    },
+    std::{
+        collections::HashMap,
+        error,
+        fs::{self, File},
+        io,
+        path::{Path, PathBuf},
+    },
+};
+
+type BoxResult<T> = Result<T, Box<dyn error::Error>>;
+
+/// Commission of every vote account at a slot
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CommissionSample {
+    pub slot: Slot,
+    pub commissions: HashMap<Pubkey, u8>,
+}
+
+/// Commission samples taken during an epoch by `sample-commissions`, several times an epoch
+/// to catch the commissions changed only around the epoch boundaries
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct EpochCommissionSamples {
+    pub epoch: Epoch,
+    pub samples: Vec<CommissionSample>,
+}
+
+impl EpochCommissionSamples {
+    fn file_name<P: AsRef<Path>>(epoch: Epoch, path: P) -> PathBuf {
+        path.as_ref().join(format!("epoch-{}.yml", epoch))
+    }
+
+    /// Samples of `epoch`, none if the epoch was not sampled
+    pub fn load<P: AsRef<Path>>(epoch: Epoch, path: P) -> Result<Self, io::Error> {
+        let file_name = Self::file_name(epoch, path);
+        if !file_name.exists() {
+            return Ok(Self {
+                epoch,
+                samples: vec![],
+            });
+        }
+        let file = File::open(file_name)?;
+        serde_yaml::from_reader(file)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), io::Error> {
+        fs::create_dir_all(&path)?;
+        let file = File::create(Self::file_name(self.epoch, path))?;
+        serde_yaml::to_writer(file, self)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))
+    }
+
+    /// Commission of the last sample of each vote account, the closest to the end of the epoch
+    pub fn last_commissions(&self) -> HashMap<Pubkey, u8> {
+        let mut samples: Vec<_> = self.samples.iter().collect();
+        samples.sort_by_key(|sample| sample.slot);
+        let mut last_commissions = HashMap::new();
+        for sample in samples {
+            last_commissions.extend(sample.commissions.clone());
+        }
+        last_commissions
+    }
+}
+
+/// Appends the commissions of the vote accounts to the samples of the current epoch in
+/// [Config::commission_samples_path]
+pub fn sample_commissions(config: &Config, data_source: &dyn ClusterDataSource) -> BoxResult<()> {
+    let epoch_info = data_source.get_epoch_info()?;
+    let vote_accounts = data_source.get_vote_accounts()?;
+    let commissions = vote_accounts
+        .current
+        .iter()
+        .chain(vote_accounts.delinquent.iter())
+        .map(|info| Ok((info.vote_pubkey.parse::<Pubkey>()?, info.commission)))
+        .collect::<BoxResult<HashMap<_, _>>>()?;
+
+    let mut epoch_samples =
+        EpochCommissionSamples::load(epoch_info.epoch, &config.commission_samples_path)?;
+    epoch_samples.samples.push(CommissionSample {
+        slot: epoch_info.absolute_slot,
+        commissions,
+    });
+    epoch_samples.save(&config.commission_samples_path)?;
+    info!(
+        "Commission sample {} of epoch {} saved at slot {} ({}/{})",
+        epoch_samples.samples.len(),
+        epoch_info.epoch,
+        epoch_info.absolute_slot,
+        epoch_info.slot_index,
+        epoch_info.slots_in_epoch
+    );
+    Ok(())
+}
+
+/// Why the validator is suspected of flipping its commission around the epoch boundary: the
+/// commission the rewards were paid with is above the commission observed when scoring
+pub fn commission_flipping_reason(
+    scored_epoch: Epoch,
+    commission: u8,
+    reward_commission: Option<u8>,
+) -> Option<String> {
+    match reward_commission {
+        Some(reward_commission) if reward_commission > commission => Some(format!(
+            "Commission changed around the epoch boundary: {}% when the rewards of epoch {} were paid, {}% when scored",
+            reward_commission, scored_epoch, commission
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_last_commissions() {
+        let vote_address = Pubkey::new_unique();
+        let other_vote_address = Pubkey::new_unique();
+        // saved in the order they were taken, not necessarily the slot order
+        let epoch_samples = EpochCommissionSamples {
+            epoch: 300,
+            samples: vec![
+                CommissionSample {
+                    slot: 200,
+                    commissions: HashMap::from([(vote_address, 100)]),
+                },
+                CommissionSample {
+                    slot: 100,
+                    commissions: HashMap::from([(vote_address, 5), (other_vote_address, 7)]),
+                },
+            ],
+        };
+        assert_eq!(
+            epoch_samples.last_commissions(),
+            HashMap::from([(vote_address, 100), (other_vote_address, 7)])
+        );
+        assert!(EpochCommissionSamples::default()
+            .last_commissions()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!(
+            "score-past-epoch-{}-commission-samples",
+            std::process::id()
+        ));
+        let vote_address = Pubkey::new_unique();
+        let epoch_samples = EpochCommissionSamples {
+            epoch: 300,
+            samples: vec![
+                CommissionSample {
+                    slot: 100,
+                    commissions: HashMap::from([(vote_address, 5)]),
+                },
+                CommissionSample {
+                    slot: 200,
+                    commissions: HashMap::from([(vote_address, 100)]),
+                },
+            ],
+        };
+        epoch_samples.save(&path).unwrap();
+
+        let loaded = EpochCommissionSamples::load(300, &path).unwrap();
+        assert_eq!(loaded.epoch, 300);
+        assert_eq!(
+            loaded
+                .samples
+                .iter()
+                .map(|sample| (sample.slot, sample.commissions.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (100, HashMap::from([(vote_address, 5)])),
+                (200, HashMap::from([(vote_address, 100)])),
+            ]
+        );
+
+        // an epoch that was not sampled
+        let loaded = EpochCommissionSamples::load(301, &path).unwrap();
+        assert_eq!(loaded.epoch, 301);
+        assert!(loaded.samples.is_empty());
+        fs::remove_dir_all(path).unwrap();
+    }
+}
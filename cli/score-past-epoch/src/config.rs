@@ -1,17 +1,22 @@
 use {
-    crate::rpc_client_utils::*,
+    crate::{
+        config_file::Profile,
+        rpc_client_utils::*,
+        scoring::{
+            scoring_model, scoring_model_names, scoring_models, DefaultModel, ScoringModel,
+            DEFAULT_SCORING_MODEL,
+        },
+    },
     clap::{
-        crate_description, crate_name, value_t, value_t_or_exit, App, AppSettings, Arg, ArgMatches,
-        SubCommand,
+        crate_description, crate_name, value_t, value_t_or_exit, App, AppSettings, Arg, SubCommand,
     },
+    cli_common::records::ArtifactFormat,
     log::*,
-    solana_clap_utils::{
-        input_parsers::lamports_of_sol,
-        input_validators::{is_amount, is_parsable, is_url, is_valid_percentage},
-    },
+    serde::{Serialize, Serializer},
+    solana_clap_utils::input_validators::{is_amount, is_parsable, is_url, is_valid_percentage},
     solana_client::rpc_client::RpcClient,
-    solana_sdk::native_token::*,
-    std::{error, path::PathBuf, time::Duration},
+    solana_sdk::{clock::Epoch, native_token::*},
+    std::{error, ops::RangeInclusive, path::PathBuf, time::Duration},
 };
 
 type BoxResult<T> = Result<T, Box<dyn error::Error>>;
@@ -25,21 +30,42 @@ fn is_release_version(string: String) -> Result<(), String> {
         .map_err(|err| format!("{:?}", err))
 }
 
-fn release_version_of(matches: &ArgMatches<'_>, name: &str) -> Option<semver::Version> {
-    matches
-        .value_of(name)
-        .map(ToString::to_string)
-        .map(|string| {
-            if string.starts_with('v') {
-                semver::Version::parse(string.split_at(1).1)
-            } else {
-                semver::Version::parse(&string)
-            }
-            .expect("semver::Version")
-        })
+fn release_version_of(string: &str) -> BoxResult<semver::Version> {
+    let version = string.strip_prefix('v').unwrap_or(string);
+    semver::Version::parse(version)
+        .map_err(|err| format!("Invalid release version {}: {}", string, err).into())
+}
+
+fn scoring_model_of(name: &str) -> BoxResult<&'static dyn ScoringModel> {
+    scoring_model(name).ok_or_else(|| {
+        format!(
+            "Unknown scoring model {}, expected one of: {}",
+            name,
+            scoring_model_names().join(", ")
+        )
+        .into()
+    })
+}
+
+fn serialize_scoring_model<S: Serializer>(
+    model: &&'static dyn ScoringModel,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(model.name())
+}
+
+fn serialize_release_version<S: Serializer>(
+    version: &Option<semver::Version>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match version {
+        Some(version) => serializer.serialize_some(&version.to_string()),
+        None => serializer.serialize_none(),
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Cluster {
     Devnet,
     Testnet,
@@ -60,11 +86,69 @@ impl std::fmt::Display for Cluster {
     }
 }
 
-#[derive(Debug)]
+/// Where the data centers of the validators come from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DataCenterProvider {
+    /// `data_center_key` of validators.app
+    ValidatorsApp,
+    /// Gossip address of the validators located in a local IP to ASN database
+    Gossip,
+}
+
+impl std::str::FromStr for DataCenterProvider {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "validators-app" => Ok(Self::ValidatorsApp),
+            "gossip" => Ok(Self::Gossip),
+            _ => Err(format!("Unknown data center provider {}", s)),
+        }
+    }
+}
+
+/// Score to discount for each percentage point of stake concentration of the ASN, country,
+/// city and hosting provider of the validator, see [crate::geo_concentration]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct GeoConcentrationPointDiscounts {
+    pub asn: u32,
+    pub country: u32,
+    pub city: u32,
+    pub hosting_provider: u32,
+}
+
+/// Serialized into every classification, without the fields specific to the run
+#[derive(Debug, Serialize)]
 pub struct Config {
+    #[serde(skip)]
     pub json_rpc_url: String,
     pub cluster: Cluster,
+    #[serde(skip)]
     pub db_path: PathBuf,
+    /// format of the validator detail written from the classification
+    #[serde(skip)]
+    pub format: ArtifactFormat,
+
+    /// save the cluster snapshot used for the classification
+    #[serde(skip)]
+    pub snapshot_out: Option<PathBuf>,
+    /// classify a saved cluster snapshot instead of the live cluster
+    #[serde(skip)]
+    pub snapshot_in: Option<PathBuf>,
+    /// score this past epoch instead of the epoch that just ended
+    pub epoch: Option<Epoch>,
+    /// save a new revision of an existing classification instead of failing
+    #[serde(skip)]
+    pub rescore: bool,
+    /// classify the missing epochs of this range instead of a single epoch
+    #[serde(skip)]
+    pub backfill_epochs: Option<RangeInclusive<Epoch>>,
+    /// record a commission sample of the vote accounts instead of classifying
+    #[serde(skip)]
+    pub sample_commissions: bool,
+    /// directory of the commission samples of the cluster, see [crate::commission_history]
+    #[serde(skip)]
+    pub commission_samples_path: PathBuf,
 
     /// compute score foll all validators in the cluster
     pub score_all: bool,
@@ -74,8 +158,16 @@ pub struct Config {
     pub score_min_stake: u64,
     /// score discount per concentration percentage point
     pub score_concentration_point_discount: u32,
+    /// score discount per concentration percentage point beyond the data center
+    pub score_geo_concentration_point_discounts: GeoConcentrationPointDiscounts,
     /// min average position considering credits_observed, 50.0 = average
     pub min_avg_position: f64,
+    /// scoring model computing the scores, see [crate::scoring::scoring_models]
+    #[serde(serialize_with = "serialize_scoring_model")]
+    pub scoring_model: &'static dyn ScoringModel,
+    /// percentage of the epoch credits added per point of the validators.app published
+    /// information and security report scores, see [crate::scoring::ValidatorsAppModel]
+    pub validators_app_bonus_pct: f64,
 
     /// Quality validators produce within this percentage of the cluster average skip rate over
     /// the previous epoch
@@ -90,6 +182,7 @@ pub struct Config {
 
     /// If Some(), destake validators with a version less than this version subject to the
     /// `max_old_release_version_percentage` limit
+    #[serde(serialize_with = "serialize_release_version")]
     pub min_release_version: Option<semver::Version>,
 
     /// Do not unstake more than this percentage of the cluster at one time for running an
@@ -107,6 +200,22 @@ pub struct Config {
     /// None: skip infrastructure concentration check
     pub max_infrastructure_concentration: Option<f64>,
 
+    /// Where the data centers of the validators come from
+    pub data_center_provider: DataCenterProvider,
+
+    /// IP to ASN database of the gossip data center provider and of the names of the hosting
+    /// providers, see [crate::ip_asn_db]
+    #[serde(skip)]
+    pub ip_asn_db_path: Option<PathBuf>,
+
+    /// directory of the validators.app responses saved by cluster and date, see
+    /// [crate::validators_app::ResponseCache]
+    #[serde(skip)]
+    pub validators_app_cache: Option<PathBuf>,
+    /// replay the latest saved validators.app response instead of requesting validators.app
+    #[serde(skip)]
+    pub validators_app_offline: bool,
+
     pub bad_cluster_average_skip_rate: usize,
 
     /// Destake if the validator's vote credits for the latest full epoch are less than this percentage
@@ -121,6 +230,14 @@ pub struct Config {
 
     /// If true, enforce the `min_self_stake_lamports` limit. If false, only warn on insufficient stake
     pub enforce_min_self_stake: bool,
+
+    /// Number of blocks of the scored epoch whose votes are read to measure the vote latency of
+    /// the validators, 0 to skip the measure
+    pub vote_latency_sample_blocks: usize,
+
+    /// Largest transaction history of an identity read to find the account that funded it, 0
+    /// to skip the lookup
+    pub identity_funder_max_signatures: usize,
 }
 
 impl Config {
@@ -130,24 +247,41 @@ impl Config {
             json_rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
             cluster: Cluster::MainnetBeta,
             db_path: PathBuf::default(),
+            format: ArtifactFormat::Csv,
+            snapshot_out: None,
+            snapshot_in: None,
+            epoch: None,
+            rescore: false,
+            backfill_epochs: None,
+            sample_commissions: false,
+            commission_samples_path: PathBuf::default(),
             score_all: false,
             score_max_commission: 8,
             score_min_stake: sol_to_lamports(100.0),
             score_concentration_point_discount: 1_500,
+            score_geo_concentration_point_discounts: GeoConcentrationPointDiscounts::default(),
             min_avg_position: 40.0,
+            scoring_model: &DefaultModel,
+            validators_app_bonus_pct: 5.0,
             quality_block_producer_percentage: 15,
             max_poor_block_producer_percentage: 20,
             max_commission: 100,
             min_release_version: None,
             max_old_release_version_percentage: 10,
             max_poor_voter_percentage: 20,
-            max_largest_dc_stake_percent: 35,
+            max_largest_dc_stake_percent: 35.0,
             max_infrastructure_concentration: Some(100.0),
+            data_center_provider: DataCenterProvider::ValidatorsApp,
+            ip_asn_db_path: None,
+            validators_app_cache: None,
+            validators_app_offline: false,
             bad_cluster_average_skip_rate: 50,
             min_epoch_credit_percentage_of_average: 50,
             min_self_stake_lamports: 0,
             max_active_stake_lamports: u64::MAX,
             enforce_min_self_stake: false,
+            vote_latency_sample_blocks: 0,
+            identity_funder_max_signatures: 0,
         }
     }
 
@@ -173,8 +307,116 @@ fn app_version() -> String {
     })
 }
 
+fn score_all_subcommand<'a, 'b>(
+    scoring_model_names: &'a [&'a str],
+    scoring_model_help: &'a str,
+) -> App<'a, 'b> {
+    SubCommand::with_name("score-all")
+        .about("Score all validators in the cluster")
+        .arg(
+            Arg::with_name("score_max_commission")
+                .long("score-max-commission")
+                .takes_value(true)
+                .required(false)
+                .help("scoring max accepted commission"),
+        )
+        .arg(
+            Arg::with_name("score_min_stake")
+                .long("score-min-stake")
+                .takes_value(true)
+                .required(false)
+                .help("scoring min stake required"),
+        )
+        .arg(
+            Arg::with_name("commission_point_discount")
+                .long("commission-point-discount")
+                .takes_value(true)
+                .required(false)
+                .help("score to discount for each commission point"),
+        )
+        .arg(
+            Arg::with_name("concentration_point_discount")
+                .long("concentration-point-discount")
+                .takes_value(true)
+                .required(false)
+                .help("score to discount for each concentration percentage point"),
+        )
+        .arg(
+            Arg::with_name("asn_concentration_point_discount")
+                .long("asn-concentration-point-discount")
+                .takes_value(true)
+                .required(false)
+                .help("score to discount for each ASN concentration percentage point"),
+        )
+        .arg(
+            Arg::with_name("country_concentration_point_discount")
+                .long("country-concentration-point-discount")
+                .takes_value(true)
+                .required(false)
+                .help("score to discount for each country concentration percentage point"),
+        )
+        .arg(
+            Arg::with_name("city_concentration_point_discount")
+                .long("city-concentration-point-discount")
+                .takes_value(true)
+                .required(false)
+                .help("score to discount for each city concentration percentage point"),
+        )
+        .arg(
+            Arg::with_name("hosting_provider_concentration_point_discount")
+                .long("hosting-provider-concentration-point-discount")
+                .takes_value(true)
+                .required(false)
+                .help("score to discount for each hosting provider concentration percentage point"),
+        )
+        .arg(
+            Arg::with_name("min_avg_position")
+                .long("min-avg-position")
+                .takes_value(true)
+                .required(false)
+                .help("min avg position required considering epoch_credits"),
+        )
+        .arg(
+            Arg::with_name("scoring_model")
+                .long("scoring-model")
+                .value_name("NAME")
+                .takes_value(true)
+                .possible_values(scoring_model_names)
+                .default_value(DEFAULT_SCORING_MODEL)
+                .help(scoring_model_help),
+        )
+        .arg(
+            Arg::with_name("validators_app_bonus_pct")
+                .long("validators-app-bonus-pct")
+                .value_name("PERCENT")
+                .takes_value(true)
+                .required(false)
+                .help(
+                    "percentage of the epoch credits added per point of the validators.app \
+                     published information and security report scores, used by the \
+                     validators-app scoring model",
+                ),
+        )
+}
+
 pub fn get_config() -> BoxResult<(Config, RpcClient)> {
+    get_config_from(std::env::args_os())
+}
+
+/// Same as [get_config] but parses the given arguments instead of the process ones,
+/// the first one being the binary name
+pub fn get_config_from<I, T>(args: I) -> BoxResult<(Config, RpcClient)>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
     let app_version = &*app_version();
+    let scoring_model_names = scoring_model_names();
+    let scoring_model_help = scoring_models()
+        .iter()
+        .map(|model| format!("{}: {}", model.name(), model.description()))
+        .collect::<Vec<_>>()
+        .join("\n");
     let matches = App::new(crate_name!())
         .about(crate_description!())
         .version(app_version)
@@ -199,6 +441,22 @@ pub fn get_config() -> BoxResult<(Config, RpcClient)> {
                 .required(true)
                 .help("Name of the cluster to operate on")
         )
+        .arg(
+            Arg::with_name("config_file")
+                .long("config")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("YAML file with the values of the arguments by profile, the arguments \
+                       given on the command line take precedence")
+        )
+        .arg(
+            Arg::with_name("profile")
+                .long("profile")
+                .value_name("NAME")
+                .takes_value(true)
+                .requires("config_file")
+                .help("Profile of the config file to use [default: the cluster name]")
+        )
         .arg(
             Arg::with_name("db_path")
                 .long("db-path")
@@ -207,6 +465,57 @@ pub fn get_config() -> BoxResult<(Config, RpcClient)> {
                 .default_value("db")
                 .help("Location for storing staking history")
         )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(ArtifactFormat::NAMES)
+                .default_value("csv")
+                .help("Format of the validator detail, <cluster>-validator-detail.<format>. \
+                       The other reports are always CSV, read by score-post-process")
+        )
+        .arg(
+            Arg::with_name("commission_samples_path")
+                .long("commission-samples-path")
+                .value_name("PATH")
+                .takes_value(true)
+                .help("Location of the commission samples taken during the epochs \
+                       [default: <db-path>/commission-samples/<cluster>]")
+        )
+        .arg(
+            Arg::with_name("snapshot_out")
+                .long("snapshot-out")
+                .value_name("FILE")
+                .takes_value(true)
+                .conflicts_with("snapshot_in")
+                .help("Save the cluster state read for the classification into this file")
+        )
+        .arg(
+            Arg::with_name("snapshot_in")
+                .long("snapshot-in")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Classify the cluster state saved with --snapshot-out, without any network access")
+        )
+        .arg(
+            Arg::with_name("epoch")
+                .long("epoch")
+                .value_name("EPOCH")
+                .takes_value(true)
+                .conflicts_with("snapshot_in")
+                .validator(is_parsable::<Epoch>)
+                .help("Score this past epoch instead of the epoch that just ended. \
+                       The stakes, commissions, releases and data centers are the current ones")
+        )
+        .arg(
+            Arg::with_name("rescore")
+                .long("rescore")
+                .takes_value(false)
+                .help("If the epoch is already classified, save the classification as a new \
+                       revision epoch-N.rR.yml and the changes from the previous revision as \
+                       epoch-N.rR.diff.yml")
+        )
         .arg(
             Arg::with_name("require_classification")
                 .long("require-classification")
@@ -309,6 +618,42 @@ pub fn get_config() -> BoxResult<(Config, RpcClient)> {
                 .validator(is_valid_percentage)
                 .help("Vote accounts sharing infrastructure with larger than this amount will not be staked")
         )
+        .arg(
+            Arg::with_name("data_center_provider")
+                .long("data-center-provider")
+                .value_name("PROVIDER")
+                .takes_value(true)
+                .possible_values(&["validators-app", "gossip"])
+                .default_value("validators-app")
+                .help("Where the data centers of the validators come from: validators.app, or \
+                       their gossip address located in the --ip-asn-db database")
+        )
+        .arg(
+            Arg::with_name("validators_app_cache")
+                .long("validators-app-cache")
+                .value_name("DIR")
+                .takes_value(true)
+                .help("Directory saving the validators.app responses by cluster and date, \
+                       validators.app is requested at most once a day")
+        )
+        .arg(
+            Arg::with_name("validators_app_offline")
+                .long("validators-app-offline")
+                .takes_value(false)
+                .requires("validators_app_cache")
+                .help("Replay the latest validators.app response saved in --validators-app-cache \
+                       instead of requesting validators.app")
+        )
+        .arg(
+            Arg::with_name("ip_asn_db")
+                .long("ip-asn-db")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("CSV IP to ASN database with the start_ip, end_ip, asn, and optionally \
+                       country and city columns of the ranges, in the layout of the ipinfo.io \
+                       exports, and the as_name of the ASNs naming their hosting provider. Required \
+                       by --data-center-provider gossip")
+        )
         .arg(
             Arg::with_name("min_self_stake")
                 .long("min-self-stake")
@@ -335,6 +680,26 @@ pub fn get_config() -> BoxResult<(Config, RpcClient)> {
                 .takes_value(false)
                 .help("Enforce the minimum self-stake requirement")
         )
+        .arg(
+            Arg::with_name("vote_latency_sample_blocks")
+                .long("vote-latency-sample-blocks")
+                .value_name("COUNT")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .default_value("20")
+                .help("Number of blocks of the scored epoch whose votes are read to measure the \
+                       vote latency of the validators, 0 to skip the measure")
+        )
+        .arg(
+            Arg::with_name("identity_funder_max_signatures")
+                .long("identity-funder-max-signatures")
+                .value_name("COUNT")
+                .takes_value(true)
+                .validator(is_parsable::<usize>)
+                .default_value("0")
+                .help("Largest transaction history of an identity read to find the account that \
+                       funded it, linking the validators of the same operator. 0 to skip the lookup")
+        )
         .arg(
             Arg::with_name("min_testnet_participation")
                 .long("min-testnet-participation")
@@ -354,66 +719,83 @@ pub fn get_config() -> BoxResult<(Config, RpcClient)> {
                 .help("Enforce the minimum testnet participation requirement.\n
                        This setting is ignored if the --cluster is not `mainnet-beta`")
         )
+        .subcommand(score_all_subcommand(&scoring_model_names, &scoring_model_help))
+        .subcommand(
+            SubCommand::with_name("backfill")
+                .about("Classify the past epochs missing from the data directory")
+                .setting(AppSettings::VersionlessSubcommands)
+                .arg(
+                    Arg::with_name("from_epoch")
+                        .long("from-epoch")
+                        .value_name("EPOCH")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_parsable::<Epoch>)
+                        .help("First epoch to score")
+                )
+                .arg(
+                    Arg::with_name("to_epoch")
+                        .long("to-epoch")
+                        .value_name("EPOCH")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_parsable::<Epoch>)
+                        .help("Last epoch to score, included")
+                )
+                .subcommand(score_all_subcommand(&scoring_model_names, &scoring_model_help))
+        )
         .subcommand(
-            SubCommand::with_name("score-all").about("Score all validators in the cluster")
-            .arg(
-                Arg::with_name("score_max_commission")
-                    .long("score-max-commission")
-                    .takes_value(true)
-                    .required(false)
-                    .help("scoring max accepted commission")
-            )
-            .arg(
-                Arg::with_name("score_min_stake")
-                    .long("score-min-stake")
-                    .takes_value(true)
-                    .required(false)
-                    .help("scoring min stake required")
-            )
-            .arg(
-                Arg::with_name("commission_point_discount")
-                    .long ("commission-point-discount")
-                    .takes_value(true)
-                    .required(false)
-                    .help("score to discount for each commission point")
-            )
-            .arg(
-                Arg::with_name("concentration_point_discount")
-                    .long ("concentration-point-discount")
-                    .takes_value(true)
-                    .required(false)
-                    .help("score to discount for each concentration percentage point")
-            )
-            .arg(
-                Arg::with_name("min_avg_position")
-                    .long ("min-avg-position")
-                    .takes_value(true)
-                    .required(false)
-                    .help("min avg position required considering epoch_credits")
-            )
-        )
-        .get_matches();
+            SubCommand::with_name("sample-commissions")
+                .about("Record the commission of every vote account, run several times an \
+                        epoch to detect the commissions changed around the epoch boundaries")
+        )
+        .get_matches_from(args);
     let cluster = match value_t_or_exit!(matches, "cluster", String).as_str() {
         "mainnet-beta" => Cluster::MainnetBeta,
         "testnet" => Cluster::Testnet,
         "devnet" => Cluster::Devnet,
         _ => unreachable!(),
     };
+    // the profile of the config file defaults to the cluster name
+    let profile = match value_t!(matches, "config_file", PathBuf) {
+        Ok(config_file) => {
+            let profile_name =
+                value_t!(matches, "profile", String).unwrap_or_else(|_| cluster.to_string());
+            info!(
+                "Config file: {}, profile: {}",
+                config_file.display(),
+                profile_name
+            );
+            Profile::load(config_file, &profile_name)?
+        }
+        Err(_) => Profile::default(),
+    };
+
     let quality_block_producer_percentage =
-        value_t_or_exit!(matches, "quality_block_producer_percentage", usize);
+        profile.required_value_t(&matches, "quality_block_producer_percentage")?;
     let min_epoch_credit_percentage_of_average =
-        value_t_or_exit!(matches, "min_epoch_credit_percentage_of_average", usize);
-    let max_commission = value_t_or_exit!(matches, "max_commission", u8);
-    let max_poor_voter_percentage = value_t_or_exit!(matches, "max_poor_voter_percentage", usize);
+        profile.required_value_t(&matches, "min_epoch_credit_percentage_of_average")?;
+    let max_commission = profile.required_value_t(&matches, "max_commission")?;
+    let max_poor_voter_percentage =
+        profile.required_value_t(&matches, "max_poor_voter_percentage")?;
     let max_poor_block_producer_percentage =
-        value_t_or_exit!(matches, "max_poor_block_producer_percentage", usize);
+        profile.required_value_t(&matches, "max_poor_block_producer_percentage")?;
     let max_old_release_version_percentage =
-        value_t_or_exit!(matches, "max_old_release_version_percentage", usize);
-    let min_release_version = release_version_of(&matches, "min_release_version");
-
-    let enforce_min_self_stake = matches.is_present("enforce_min_self_stake");
-    let min_self_stake_lamports = lamports_of_sol(&matches, "min_self_stake").unwrap();
-    let max_active_stake_lamports = lamports_of_sol(&matches, "max_active_stake").unwrap();
+        profile.required_value_t(&matches, "max_old_release_version_percentage")?;
+    let min_release_version = profile
+        .value_of(&matches, "min_release_version")?
+        .map(|version| release_version_of(&version))
+        .transpose()?;
+
+    let enforce_min_self_stake = profile.is_present(&matches, "enforce_min_self_stake")?;
+    let min_self_stake_lamports =
+        sol_to_lamports(profile.required_value_t(&matches, "min_self_stake")?);
+    let max_active_stake_lamports =
+        sol_to_lamports(profile.required_value_t(&matches, "max_active_stake")?);
+    let vote_latency_sample_blocks =
+        profile.required_value_t(&matches, "vote_latency_sample_blocks")?;
+    let identity_funder_max_signatures =
+        profile.required_value_t(&matches, "identity_funder_max_signatures")?;
 
     let json_rpc_url = match cluster {
         Cluster::MainnetBeta => value_t!(matches, "json_rpc_url", String)
@@ -424,13 +806,63 @@ pub fn get_config() -> BoxResult<(Config, RpcClient)> {
             .unwrap_or_else(|_| "http://api.devnet.solana.com".into()),
     };
     let db_path = value_t_or_exit!(matches, "db_path", PathBuf);
+    let format = value_t_or_exit!(matches, "format", ArtifactFormat);
+    let commission_samples_path = value_t!(matches, "commission_samples_path", PathBuf)
+        .unwrap_or_else(|_| db_path.join("commission-samples").join(cluster.to_string()));
+    let snapshot_out = value_t!(matches, "snapshot_out", PathBuf).ok();
+    let snapshot_in = value_t!(matches, "snapshot_in", PathBuf).ok();
+    let epoch = value_t!(matches, "epoch", Epoch).ok();
+    let rescore = matches.is_present("rescore");
 
     let bad_cluster_average_skip_rate =
-        value_t!(matches, "bad_cluster_average_skip_rate", usize).unwrap_or(50);
+        profile.required_value_t(&matches, "bad_cluster_average_skip_rate")?;
     let max_largest_dc_stake_percent =
-        value_t_or_exit!(matches, "max_largest_dc_stake_percent", f64);
+        profile.required_value_t(&matches, "max_largest_dc_stake_percent")?;
     let max_infrastructure_concentration =
-        value_t!(matches, "max_infrastructure_concentration", f64).ok();
+        profile.value_t(&matches, "max_infrastructure_concentration")?;
+    let data_center_provider = profile.required_value_t(&matches, "data_center_provider")?;
+    let ip_asn_db_path = profile.value_of(&matches, "ip_asn_db")?.map(PathBuf::from);
+    let validators_app_cache = value_t!(matches, "validators_app_cache", PathBuf).ok();
+    let validators_app_offline = matches.is_present("validators_app_offline");
+    if data_center_provider == DataCenterProvider::Gossip
+        && ip_asn_db_path.is_none()
+        && snapshot_in.is_none()
+    {
+        return Err("--data-center-provider gossip requires --ip-asn-db".into());
+    }
+
+    let sample_commissions = matches.subcommand_name() == Some("sample-commissions");
+    if sample_commissions
+        && (snapshot_in.is_some() || snapshot_out.is_some() || epoch.is_some() || rescore)
+    {
+        return Err(
+            "--snapshot-in, --snapshot-out, --epoch and --rescore do not apply to sample-commissions"
+                .into(),
+        );
+    }
+
+    // backfill command, the score-all command can follow it
+    let (backfill_epochs, score_all_matches) = match matches.subcommand() {
+        ("backfill", Some(matches)) => {
+            if snapshot_in.is_some() || snapshot_out.is_some() || epoch.is_some() || rescore {
+                return Err(
+                    "--snapshot-in, --snapshot-out, --epoch and --rescore do not apply to backfill"
+                        .into(),
+                );
+            }
+            let from_epoch = value_t_or_exit!(matches, "from_epoch", Epoch);
+            let to_epoch = value_t_or_exit!(matches, "to_epoch", Epoch);
+            if from_epoch > to_epoch {
+                return Err(format!(
+                    "--from-epoch {} is after --to-epoch {}",
+                    from_epoch, to_epoch
+                )
+                .into());
+            }
+            (Some(from_epoch..=to_epoch), matches.subcommand())
+        }
+        subcommand => (None, subcommand),
+    };
 
     // score-all command and arguments
     let (
@@ -438,27 +870,82 @@ pub fn get_config() -> BoxResult<(Config, RpcClient)> {
         score_max_commission,
         score_min_stake,
         score_concentration_point_discount,
+        score_geo_concentration_point_discounts,
         min_avg_position,
-    ) = match matches.subcommand() {
-        ("score-all", Some(matches)) => (
-            true,
-            value_t!(matches, "score_max_commission", u8).unwrap_or(10),
-            value_t!(matches, "score_min_stake", u64).unwrap_or(sol_to_lamports(100.0)),
-            value_t!(matches, "concentration_point_discount", u32).unwrap_or(2000),
-            value_t!(matches, "min_avg_position", f64).unwrap_or(50.0),
+        scoring_model,
+        validators_app_bonus_pct,
+    ) = match score_all_matches {
+        ("score-all", Some(matches)) => {
+            let profile = profile.subcommand("score-all");
+            let score_all_config = (
+                true,
+                profile
+                    .value_t(matches, "score_max_commission")?
+                    .unwrap_or(10),
+                profile
+                    .value_t(matches, "score_min_stake")?
+                    .unwrap_or(sol_to_lamports(100.0)),
+                profile
+                    .value_t(matches, "concentration_point_discount")?
+                    .unwrap_or(2000),
+                GeoConcentrationPointDiscounts {
+                    asn: profile
+                        .value_t(matches, "asn_concentration_point_discount")?
+                        .unwrap_or(0),
+                    country: profile
+                        .value_t(matches, "country_concentration_point_discount")?
+                        .unwrap_or(0),
+                    city: profile
+                        .value_t(matches, "city_concentration_point_discount")?
+                        .unwrap_or(0),
+                    hosting_provider: profile
+                        .value_t(matches, "hosting_provider_concentration_point_discount")?
+                        .unwrap_or(0),
+                },
+                profile
+                    .value_t(matches, "min_avg_position")?
+                    .unwrap_or(50.0),
+                scoring_model_of(&profile.required_value_t::<String>(matches, "scoring_model")?)?,
+                profile
+                    .value_t(matches, "validators_app_bonus_pct")?
+                    .unwrap_or(5.0),
+            );
+            profile.check_unused_keys(&[])?;
+            score_all_config
+        }
+        _ => (
+            false,
+            0,
+            0,
+            0,
+            GeoConcentrationPointDiscounts::default(),
+            0.0,
+            &DefaultModel as &dyn ScoringModel,
+            0.0,
         ),
-        _ => (false, 0, 0, 0, 0.0),
     };
+    profile.check_unused_keys(&["score-all"])?;
 
     let config = Config {
         json_rpc_url,
         cluster,
         db_path,
+        format,
+        snapshot_out,
+        snapshot_in,
+        epoch,
+        rescore,
+        backfill_epochs,
+        sample_commissions,
+        commission_samples_path,
         score_all,
         score_max_commission,
         score_min_stake,
         score_concentration_point_discount,
+        score_geo_concentration_point_discounts,
         min_avg_position,
+        scoring_model,
+        validators_app_bonus_pct,
         quality_block_producer_percentage,
         max_poor_block_producer_percentage,
         max_commission,
@@ -467,18 +954,27 @@ pub fn get_config() -> BoxResult<(Config, RpcClient)> {
         max_poor_voter_percentage,
         max_largest_dc_stake_percent,
         max_infrastructure_concentration,
+        data_center_provider,
+        ip_asn_db_path,
+        validators_app_cache,
+        validators_app_offline,
         bad_cluster_average_skip_rate,
         min_epoch_credit_percentage_of_average,
         min_self_stake_lamports,
         max_active_stake_lamports,
         enforce_min_self_stake,
+        vote_latency_sample_blocks,
+        identity_funder_max_signatures,
     };
 
     info!("RPC URL: {}", config.json_rpc_url);
     let rpc_client =
         RpcClient::new_with_timeout(config.json_rpc_url.clone(), Duration::from_secs(180));
 
-    rpc_client_health_check(&rpc_client);
+    // a snapshot replay does not use the cluster
+    if config.snapshot_in.is_none() {
+        rpc_client_health_check(&rpc_client);
+    }
 
     Ok((config, rpc_client))
 }
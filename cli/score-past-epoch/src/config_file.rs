@@ -0,0 +1,312 @@
+use {
+    clap::ArgMatches,
+    serde_yaml::{Mapping, Value},
+    std::{cell::RefCell, collections::BTreeSet, error, fs::File, path::Path, str::FromStr},
+};
+
+type BoxResult<T> = Result<T, Box<dyn error::Error>>;
+
+/// Values of the command line arguments read from a profile of the config file, keyed by the
+/// long name of the argument:
+///
+/// ```yaml
+/// mainnet-beta:
+///   min-release-version: 1.10.32
+///   max-old-release-version-percentage: 30
+///   score-all:
+///     score-max-commission: 10
+/// ```
+///
+/// The arguments given on the command line override the profile, the profile overrides the
+/// defaults of the arguments.
+#[derive(Debug, Default)]
+pub struct Profile {
+    name: String,
+    values: Mapping,
+    used_keys: RefCell<BTreeSet<String>>,
+}
+
+impl Profile {
+    /// Loads the profile `name` of the YAML config file `path`
+    pub fn load<P: AsRef<Path>>(path: P, name: &str) -> BoxResult<Self> {
+        let file = File::open(&path).map_err(|err| {
+            format!(
+                "Unable to open the config file {}: {}",
+                path.as_ref().display(),
+                err
+            )
+        })?;
+        let mut profiles: Mapping = serde_yaml::from_reader(file)?;
+        let values = match profiles.remove(&Value::String(name.into())) {
+            Some(Value::Mapping(values)) => values,
+            Some(_) => return Err(format!("Profile {} is not a mapping", name).into()),
+            None => {
+                return Err(
+                    format!("Profile {} not found in {}", name, path.as_ref().display()).into(),
+                )
+            }
+        };
+        Ok(Self {
+            name: name.into(),
+            values,
+            used_keys: RefCell::default(),
+        })
+    }
+
+    /// Profile of the values nested under the subcommand `name`
+    pub fn subcommand(&self, name: &str) -> Self {
+        let values = match self.get(name) {
+            Some(Value::Mapping(values)) => values,
+            _ => Mapping::new(),
+        };
+        Self {
+            name: format!("{}.{}", self.name, name),
+            values,
+            used_keys: RefCell::default(),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<Value> {
+        let value = self.values.get(&Value::String(key.into())).cloned();
+        if value.is_some() {
+            self.used_keys.borrow_mut().insert(key.into());
+        }
+        value
+    }
+
+    /// Value of the argument `name`, from the command line, then the profile, then the default
+    pub fn value_of(&self, matches: &ArgMatches<'_>, name: &str) -> BoxResult<Option<String>> {
+        if matches.occurrences_of(name) > 0 {
+            return Ok(matches.value_of(name).map(String::from));
+        }
+        match self.get(&name.replace('_', "-")) {
+            Some(Value::String(value)) => Ok(Some(value)),
+            Some(Value::Number(value)) => Ok(Some(value.to_string())),
+            Some(value) => Err(format!(
+                "Profile {}: {} must be a string or a number, found {:?}",
+                self.name,
+                name.replace('_', "-"),
+                value
+            )
+            .into()),
+            None => Ok(matches.value_of(name).map(String::from)),
+        }
+    }
+
+    /// Same as [Profile::value_of], parsing the value
+    pub fn value_t<T>(&self, matches: &ArgMatches<'_>, name: &str) -> BoxResult<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.value_of(matches, name)?
+            .map(|value| {
+                value.parse::<T>().map_err(|err| {
+                    format!(
+                        "Invalid value {} for {}: {}",
+                        value,
+                        name.replace('_', "-"),
+                        err
+                    )
+                    .into()
+                })
+            })
+            .transpose()
+    }
+
+    /// Same as [Profile::value_t] for the arguments with a default value
+    pub fn required_value_t<T>(&self, matches: &ArgMatches<'_>, name: &str) -> BoxResult<T>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.value_t(matches, name)?
+            .ok_or_else(|| format!("{} is required", name.replace('_', "-")).into())
+    }
+
+    /// Whether the flag `name` is set on the command line or in the profile
+    pub fn is_present(&self, matches: &ArgMatches<'_>, name: &str) -> BoxResult<bool> {
+        if matches.is_present(name) {
+            return Ok(true);
+        }
+        match self.get(&name.replace('_', "-")) {
+            Some(Value::Bool(value)) => Ok(value),
+            Some(value) => Err(format!(
+                "Profile {}: {} must be a boolean, found {:?}",
+                self.name,
+                name.replace('_', "-"),
+                value
+            )
+            .into()),
+            None => Ok(false),
+        }
+    }
+
+    /// Fails on the keys of the profile not read as an argument, most likely misspelled
+    pub fn check_unused_keys(&self, subcommands: &[&str]) -> BoxResult<()> {
+        let used_keys = self.used_keys.borrow();
+        let unused_keys: Vec<_> = self
+            .values
+            .iter()
+            .filter_map(|(key, _)| key.as_str())
+            .filter(|key| !used_keys.contains(*key) && !subcommands.contains(key))
+            .collect();
+        if unused_keys.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Profile {}: unknown arguments {}",
+                self.name,
+                unused_keys.join(", ")
+            )
+            .into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::config::{get_config_from, Config},
+        std::path::PathBuf,
+    };
+
+    fn shipped_config_file() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config/score-past-epoch.yml")
+    }
+
+    fn config_file(name: &str, contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("score-past-epoch-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// Config of the arguments, replaying a snapshot so the cluster is not contacted
+    fn config_of(config_file: &Path, args: &[&str]) -> BoxResult<Config> {
+        let config_file = config_file.display().to_string();
+        let mut all_args = vec![
+            "score-past-epoch",
+            "--config",
+            &config_file,
+            "--snapshot-in",
+            "cluster-snapshot.yml",
+        ];
+        all_args.extend(args);
+        get_config_from(all_args).map(|(config, _)| config)
+    }
+
+    #[test]
+    fn test_load_shipped_profiles() {
+        let config = config_of(
+            &shipped_config_file(),
+            &["--cluster", "mainnet-beta", "score-all"],
+        )
+        .unwrap();
+        assert_eq!(
+            config.min_release_version,
+            Some(semver::Version::parse("1.10.32").unwrap())
+        );
+        assert_eq!(config.max_old_release_version_percentage, 30);
+        assert!(config.score_all);
+        assert_eq!(config.score_max_commission, 10);
+        assert_eq!(config.score_concentration_point_discount, 1500);
+        assert_eq!(config.min_avg_position, 50.0);
+
+        let config = config_of(
+            &shipped_config_file(),
+            &["--cluster", "testnet", "score-all"],
+        )
+        .unwrap();
+        assert_eq!(
+            config.min_release_version,
+            Some(semver::Version::parse("1.9.15").unwrap())
+        );
+        assert_eq!(config.max_old_release_version_percentage, 10);
+
+        let config = config_of(
+            &shipped_config_file(),
+            &["--cluster", "devnet", "score-all"],
+        )
+        .unwrap();
+        assert_eq!(config.max_largest_dc_stake_percent, 100.0);
+        assert_eq!(config.max_poor_voter_percentage, 100);
+        assert_eq!(config.score_max_commission, 100);
+    }
+
+    #[test]
+    fn test_arguments_override_the_profile() {
+        let config = config_of(
+            &shipped_config_file(),
+            &[
+                "--cluster",
+                "mainnet-beta",
+                "--max-old-release-version-percentage",
+                "50",
+                "score-all",
+                "--min-avg-position",
+                "40",
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.max_old_release_version_percentage, 50);
+        assert_eq!(config.min_avg_position, 40.0);
+        // the values not given on the command line still come from the profile
+        assert_eq!(
+            config.min_release_version,
+            Some(semver::Version::parse("1.10.32").unwrap())
+        );
+        assert_eq!(config.score_max_commission, 10);
+
+        // --profile selects another profile than the cluster name
+        let config = config_of(
+            &shipped_config_file(),
+            &["--cluster", "testnet", "--profile", "devnet"],
+        )
+        .unwrap();
+        assert_eq!(config.max_poor_voter_percentage, 100);
+    }
+
+    #[test]
+    fn test_misspelled_keys() {
+        let path = config_file(
+            "misspelled.yml",
+            "testnet:\n  max-old-release-version-percentag: 30\n",
+        );
+        let error = config_of(&path, &["--cluster", "testnet"]).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Profile testnet: unknown arguments max-old-release-version-percentag"
+        );
+        std::fs::remove_file(path).unwrap();
+
+        let path = config_file(
+            "misspelled-score-all.yml",
+            "testnet:\n  score-all:\n    score-max-comission: 10\n",
+        );
+        let error = config_of(&path, &["--cluster", "testnet", "score-all"])
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Profile testnet.score-all: unknown arguments score-max-comission"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_profiles() {
+        let path = config_file(
+            "invalid.yml",
+            "testnet:\n  enforce-min-self-stake: 10\nother: 1\n",
+        );
+        assert!(Profile::load(&path, "devnet").is_err());
+        assert!(Profile::load(&path, "other").is_err());
+        let error = config_of(&path, &["--cluster", "testnet"]).err().unwrap();
+        assert!(error
+            .to_string()
+            .contains("enforce-min-self-stake must be a boolean"));
+        std::fs::remove_file(path).unwrap();
+    }
+}
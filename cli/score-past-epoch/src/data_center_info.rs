@@ -1,7 +1,8 @@
 use {
-    crate::{validators_app, ByIdentityInfo},
+    crate::{ip_asn_db::IpAsnDb, validators_app, ByIdentityInfo},
     log::*,
     serde::{Deserialize, Serialize},
+    solana_client::rpc_response::{RpcContactInfo, RpcVoteAccountStatus},
     solana_sdk::pubkey::Pubkey,
     std::{collections::HashMap, error, str::FromStr},
 };
@@ -78,7 +79,14 @@ pub struct DataCenters {
     pub by_identity: HashMap<Pubkey, ByIdentityInfo>,
 }
 
-pub fn get(cluster: &str) -> Result<DataCenters, Box<dyn error::Error>> {
+/// Fetches the validators of the cluster from validators.app, `None` for devnet. With a
+/// `cache`, the response of the day is requested once and saved, `offline` replays the latest
+/// saved response without requesting validators.app.
+pub fn fetch_validators_app(
+    cluster: &str,
+    cache: Option<&validators_app::ResponseCache>,
+    offline: bool,
+) -> Result<Option<validators_app::ValidatorsResponse>, Box<dyn error::Error>> {
     let cluster_json = match cluster {
         "mainnet-beta" => validators_app::ClusterJson::MainnetBeta,
         "testnet" => validators_app::ClusterJson::Testnet,
@@ -87,15 +95,21 @@ pub fn get(cluster: &str) -> Result<DataCenters, Box<dyn error::Error>> {
     };
 
     if cluster == "devnet" {
-        return Ok(DataCenters {
-            info: vec![DataCenterInfo {
-                id: DataCenterId {
-                    ..Default::default()
-                },
-                ..Default::default()
-            }],
-            ..Default::default()
-        });
+        return Ok(None);
+    }
+
+    if offline {
+        return match cache
+            .map(|cache| cache.load_latest())
+            .transpose()?
+            .flatten()
+        {
+            Some(validators) => Ok(Some(validators)),
+            None => Err(format!("No validators.app response of {} saved", cluster).into()),
+        };
+    }
+    if let Some(validators) = cache.map(|cache| cache.load_today()).transpose()?.flatten() {
+        return Ok(Some(validators));
     }
 
     let token = std::env::var("VALIDATORS_APP_TOKEN")
@@ -104,7 +118,38 @@ pub fn get(cluster: &str) -> Result<DataCenters, Box<dyn error::Error>> {
         Err("Env variable VALIDATORS_APP_TOKEN has got empty value")?;
     };
     let client = validators_app::Client::new(token, cluster_json);
+    fetch_with_client(&client, cache).map(Some)
+}
+
+fn fetch_with_client(
+    client: &validators_app::Client,
+    cache: Option<&validators_app::ResponseCache>,
+) -> Result<validators_app::ValidatorsResponse, Box<dyn error::Error>> {
     let validators = client.validators(None, None)?;
+    if let Some(cache) = cache {
+        cache.save(&validators)?;
+    }
+    Ok(validators)
+}
+
+/// Data centers of the validators reported by validators.app, everybody in an unknown
+/// data center without the report
+pub fn from_validators_app(validators: Option<&validators_app::ValidatorsResponse>) -> DataCenters {
+    let validators = match validators {
+        Some(validators) => validators,
+        None => {
+            return DataCenters {
+                info: vec![DataCenterInfo {
+                    id: DataCenterId {
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }
+        }
+    };
+
     let mut data_center_map = HashMap::new();
     let mut total_stake = 0;
     let mut unknown_data_center_stake: u64 = 0;
@@ -146,6 +191,7 @@ pub fn get(cluster: &str) -> Result<DataCenters, Box<dyn error::Error>> {
                 keybase_id: String::from(v.keybase_id.as_deref().unwrap_or("")),
                 name: String::from(v.name.as_deref().unwrap_or("")),
                 www_url: String::from(v.www_url.as_deref().unwrap_or("")),
+                scores: v.into(),
             },
         );
 
@@ -157,17 +203,152 @@ pub fn get(cluster: &str) -> Result<DataCenters, Box<dyn error::Error>> {
         data_center_info.validators.push(identity);
     }
 
+    DataCenters {
+        info: data_center_infos(data_center_map, total_stake, unknown_data_center_stake),
+        by_identity,
+    }
+}
+
+/// Data centers of the cluster nodes found from their gossip address in `ip_asn_db`, the
+/// nodes missing from the database are left out
+pub fn locate_cluster_nodes(
+    cluster_nodes: &[RpcContactInfo],
+    ip_asn_db: &IpAsnDb,
+) -> HashMap<Pubkey, DataCenterId> {
+    let data_centers: HashMap<_, _> = cluster_nodes
+        .iter()
+        .filter_map(|contact_info| {
+            let identity = Pubkey::from_str(&contact_info.pubkey).ok()?;
+            let data_center_id = ip_asn_db.lookup(contact_info.gossip?.ip())?;
+            Some((identity, data_center_id.clone()))
+        })
+        .collect();
+    info!(
+        "{} of {} cluster nodes located from their gossip address",
+        data_centers.len(),
+        cluster_nodes.len()
+    );
+    data_centers
+}
+
+/// Data centers of the validators located from their gossip address, see
+/// [locate_cluster_nodes], with the stake of their vote accounts. The keybase ids, names and
+/// urls still come from validators.app when available.
+pub fn from_gossip(
+    gossip_data_centers: &HashMap<Pubkey, DataCenterId>,
+    vote_accounts: &RpcVoteAccountStatus,
+    validators: Option<&validators_app::ValidatorsResponse>,
+) -> DataCenters {
+    let mut by_identity = from_validators_app(validators).by_identity;
+    for (identity, info) in by_identity.iter_mut() {
+        info.data_center_id = gossip_data_centers
+            .get(identity)
+            .cloned()
+            .unwrap_or_default();
+    }
+
+    let mut stake_by_identity: HashMap<Pubkey, u64> = HashMap::new();
+    for vote_account_info in vote_accounts
+        .current
+        .iter()
+        .chain(vote_accounts.delinquent.iter())
+    {
+        if let Ok(identity) = Pubkey::from_str(&vote_account_info.node_pubkey) {
+            *stake_by_identity.entry(identity).or_default() += vote_account_info.activated_stake;
+        }
+    }
+
+    let mut data_center_map = HashMap::new();
+    let mut total_stake = 0;
+    let mut unknown_data_center_stake: u64 = 0;
+    for (identity, stake) in stake_by_identity {
+        let data_center_id = match gossip_data_centers.get(&identity) {
+            Some(data_center_id) => data_center_id.clone(),
+            None => {
+                unknown_data_center_stake = unknown_data_center_stake.saturating_add(stake);
+                DataCenterId::default()
+            }
+        };
+        by_identity.entry(identity).or_default().data_center_id = data_center_id.clone();
+
+        let data_center_info = data_center_map
+            .entry(data_center_id.clone())
+            .or_insert_with(|| DataCenterInfo::new(data_center_id));
+        data_center_info.stake += stake;
+        total_stake += stake;
+        data_center_info.validators.push(identity);
+    }
+
+    DataCenters {
+        info: data_center_infos(data_center_map, total_stake, unknown_data_center_stake),
+        by_identity,
+    }
+}
+
+fn data_center_infos(
+    mut data_center_map: HashMap<DataCenterId, DataCenterInfo>,
+    total_stake: u64,
+    unknown_data_center_stake: u64,
+) -> Vec<DataCenterInfo> {
     let unknown_percent = 100f64 * (unknown_data_center_stake as f64) / total_stake as f64;
     if unknown_percent > 3f64 {
         warn!("unknown data center percentage: {:.0}%", unknown_percent);
     }
 
-    let info = data_center_map
+    data_center_map
         .drain()
         .map(|(_, mut i)| {
             i.stake_percent = 100f64 * i.stake as f64 / total_stake as f64;
             i
         })
-        .collect();
-    Ok(DataCenters { info, by_identity })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::validators_app::{
+            test::{stand_in_client, stand_in_server, MAINNET_FIXTURE},
+            ResponseCache,
+        },
+    };
+
+    #[test]
+    fn test_from_validators_app_stand_in() {
+        let client = stand_in_client(stand_in_server(vec![(500, ""), (200, MAINNET_FIXTURE)]));
+        let dir =
+            std::env::temp_dir().join(format!("data-center-info-test-{}", std::process::id()));
+        let cache = ResponseCache::new(&dir, "mainnet-beta");
+        let validators = fetch_with_client(&client, Some(&cache)).unwrap();
+
+        // replayed from the cache without requesting validators.app
+        let replayed = fetch_validators_app("mainnet-beta", Some(&cache), true)
+            .unwrap()
+            .unwrap();
+        assert_eq!(replayed.as_ref().len(), validators.as_ref().len());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let data_centers = from_validators_app(Some(&validators));
+        assert_eq!(data_centers.by_identity.len(), 3);
+        assert_eq!(data_centers.info.len(), 3);
+        let stake_percent = |data_center_id: &str| {
+            data_centers
+                .info
+                .iter()
+                .find(|info| info.id.to_string() == data_center_id)
+                .unwrap()
+                .stake_percent
+        };
+        assert!((stake_percent("24940-DE-Falkenstein") - 65.6).abs() < 0.1);
+        assert!((stake_percent("0-Unknown") - 1.0).abs() < 0.1);
+
+        let identity = Pubkey::from_str("9yogeAWdkwXhaRJcdCbsp5mpJ6APJ2H4sZyWGDTLoazC").unwrap();
+        let info = data_centers.by_identity.get(&identity).unwrap();
+        assert_eq!(info.name, "Alpha Validator");
+        assert_eq!(info.data_center_id.to_string(), "24940-DE-Falkenstein");
+        assert_eq!(info.scores.published_information_score, Some(1));
+        assert_eq!(info.scores.security_report_score, Some(1));
+        assert_eq!(info.scores.ping_time, Some(12.5));
+    }
 }
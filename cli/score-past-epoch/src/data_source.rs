@@ -0,0 +1,325 @@
+use {
+    log::*,
+    solana_client::{
+        rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+        rpc_config::RpcBlockConfig,
+        rpc_response::{
+            RpcContactInfo, RpcInflationReward, RpcLeaderSchedule, RpcVoteAccountStatus,
+        },
+    },
+    solana_foundation_delegation_program_cli::get_participants_with_state,
+    solana_foundation_delegation_program_registry::state::{Participant, ParticipantState},
+    solana_sdk::{
+        account::Account,
+        clock::{Epoch, Slot},
+        commitment_config::CommitmentConfig,
+        epoch_info::EpochInfo,
+        epoch_schedule::EpochSchedule,
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::Transaction,
+    },
+    solana_transaction_status::{TransactionDetails, UiTransactionEncoding},
+    std::{
+        collections::{BTreeSet, HashMap},
+        error,
+        str::FromStr,
+    },
+};
+
+type BoxResult<T> = Result<T, Box<dyn error::Error>>;
+
+/// The cluster reads of the classification, see [crate::snapshot::ClusterSnapshot::capture]
+pub trait ClusterDataSource {
+    fn get_epoch_info(&self) -> BoxResult<EpochInfo>;
+
+    fn get_epoch_schedule(&self) -> BoxResult<EpochSchedule>;
+
+    fn get_vote_accounts(&self) -> BoxResult<RpcVoteAccountStatus>;
+
+    fn get_cluster_nodes(&self) -> BoxResult<Vec<RpcContactInfo>>;
+
+    /// Leader schedule of the epoch containing `slot`
+    fn get_leader_schedule(&self, slot: Slot) -> BoxResult<Option<RpcLeaderSchedule>>;
+
+    /// Finalized account, sysvars included
+    fn get_account(&self, pubkey: &Pubkey) -> BoxResult<Option<Account>>;
+
+    fn get_program_accounts(&self, program_id: &Pubkey) -> BoxResult<Vec<(Pubkey, Account)>>;
+
+    /// Confirmed blocks in `[start_slot, end_slot]`, from the ledger history
+    fn get_blocks(&self, start_slot: Slot, end_slot: Slot) -> BoxResult<Vec<Slot>>;
+
+    /// Transactions of the confirmed block of `slot` executed without error
+    fn get_block_transactions(&self, slot: Slot) -> BoxResult<Vec<Transaction>>;
+
+    /// Inflation rewards of `epoch` paid to `addresses`, `None` for the addresses not rewarded
+    fn get_inflation_rewards(
+        &self,
+        addresses: &[Pubkey],
+        epoch: Epoch,
+    ) -> BoxResult<Vec<Option<RpcInflationReward>>>;
+
+    /// First transaction of `address`, `None` when the history of the address is longer than
+    /// `max_signatures` transactions or not served by the RPC node
+    fn get_oldest_transaction(
+        &self,
+        address: &Pubkey,
+        max_signatures: usize,
+    ) -> BoxResult<Option<Transaction>>;
+
+    /// Participants of the stake-o-matic registry by participant address
+    fn get_participants(
+        &self,
+        state: Option<ParticipantState>,
+    ) -> BoxResult<HashMap<Pubkey, Participant>>;
+}
+
+impl ClusterDataSource for RpcClient {
+    fn get_epoch_info(&self) -> BoxResult<EpochInfo> {
+        Ok(RpcClient::get_epoch_info(self)?)
+    }
+
+    fn get_epoch_schedule(&self) -> BoxResult<EpochSchedule> {
+        Ok(RpcClient::get_epoch_schedule(self)?)
+    }
+
+    fn get_vote_accounts(&self) -> BoxResult<RpcVoteAccountStatus> {
+        Ok(RpcClient::get_vote_accounts(self)?)
+    }
+
+    fn get_cluster_nodes(&self) -> BoxResult<Vec<RpcContactInfo>> {
+        Ok(RpcClient::get_cluster_nodes(self)?)
+    }
+
+    fn get_leader_schedule(&self, slot: Slot) -> BoxResult<Option<RpcLeaderSchedule>> {
+        Ok(self.get_leader_schedule_with_commitment(Some(slot), CommitmentConfig::finalized())?)
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> BoxResult<Option<Account>> {
+        Ok(self
+            .get_account_with_commitment(pubkey, CommitmentConfig::finalized())?
+            .value)
+    }
+
+    fn get_program_accounts(&self, program_id: &Pubkey) -> BoxResult<Vec<(Pubkey, Account)>> {
+        Ok(RpcClient::get_program_accounts(self, program_id)?)
+    }
+
+    fn get_blocks(&self, start_slot: Slot, end_slot: Slot) -> BoxResult<Vec<Slot>> {
+        // an epoch is within the 500K slots range served by a single request
+        Ok(RpcClient::get_blocks(self, start_slot, Some(end_slot))?)
+    }
+
+    fn get_block_transactions(&self, slot: Slot) -> BoxResult<Vec<Transaction>> {
+        let block = self.get_block_with_config(
+            slot,
+            RpcBlockConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                transaction_details: Some(TransactionDetails::Full),
+                rewards: Some(false),
+                commitment: Some(CommitmentConfig::finalized()),
+            },
+        )?;
+        Ok(block
+            .transactions
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|transaction| {
+                transaction
+                    .meta
+                    .as_ref()
+                    .map_or(false, |meta| meta.err.is_none())
+            })
+            .filter_map(|transaction| transaction.transaction.decode())
+            .collect())
+    }
+
+    fn get_inflation_rewards(
+        &self,
+        addresses: &[Pubkey],
+        epoch: Epoch,
+    ) -> BoxResult<Vec<Option<RpcInflationReward>>> {
+        // the RPC nodes limit the number of addresses of a request
+        let mut inflation_rewards = Vec::with_capacity(addresses.len());
+        for addresses in addresses.chunks(100) {
+            inflation_rewards.extend(self.get_inflation_reward(addresses, Some(epoch))?);
+        }
+        Ok(inflation_rewards)
+    }
+
+    fn get_oldest_transaction(
+        &self,
+        address: &Pubkey,
+        max_signatures: usize,
+    ) -> BoxResult<Option<Transaction>> {
+        // the RPC nodes return at most 1000 signatures a request, newest first
+        let mut before = None;
+        let mut signatures = 0;
+        while signatures < max_signatures {
+            let page = self.get_signatures_for_address_with_config(
+                address,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: Some(1000),
+                    commitment: Some(CommitmentConfig::finalized()),
+                },
+            )?;
+            signatures += page.len();
+            let oldest = match page.last() {
+                Some(oldest) => Signature::from_str(&oldest.signature)?,
+                None => return Ok(None),
+            };
+            if page.len() < 1000 {
+                let transaction = self.get_transaction(&oldest, UiTransactionEncoding::Base64)?;
+                return Ok(transaction.transaction.transaction.decode());
+            }
+            before = Some(oldest);
+        }
+        Ok(None)
+    }
+
+    fn get_participants(
+        &self,
+        state: Option<ParticipantState>,
+    ) -> BoxResult<HashMap<Pubkey, Participant>> {
+        let participants: HashMap<_, _> = get_participants_with_state(self, state)?
+            .into_iter()
+            .collect();
+        info!("{} participants loaded", participants.len());
+        // sanity check of the registry response
+        assert!(participants.len() > 450);
+        Ok(participants)
+    }
+}
+
+/// Cluster kept in memory, to classify synthetic clusters in tests
+pub struct InMemoryDataSource {
+    pub epoch_info: EpochInfo,
+    pub epoch_schedule: EpochSchedule,
+    pub vote_accounts: RpcVoteAccountStatus,
+    pub cluster_nodes: Vec<RpcContactInfo>,
+    pub leader_schedules: HashMap<Epoch, RpcLeaderSchedule>,
+    pub accounts: HashMap<Pubkey, Account>,
+    /// Confirmed blocks of the ledger, including the slots past the SlotHistory sysvar
+    pub blocks: BTreeSet<Slot>,
+    /// Successful transactions of the blocks, none if missing
+    pub block_transactions: HashMap<Slot, Vec<Transaction>>,
+    pub inflation_rewards: HashMap<Epoch, HashMap<Pubkey, RpcInflationReward>>,
+    /// First transaction by address, none if missing
+    pub oldest_transactions: HashMap<Pubkey, Transaction>,
+    pub participants: HashMap<Pubkey, Participant>,
+}
+
+impl InMemoryDataSource {
+    /// Empty cluster at the first slot of `epoch`
+    pub fn new(epoch: Epoch, epoch_schedule: EpochSchedule) -> Self {
+        let first_slot = epoch_schedule.get_first_slot_in_epoch(epoch);
+        Self {
+            epoch_info: EpochInfo {
+                epoch,
+                slot_index: 0,
+                slots_in_epoch: epoch_schedule.get_slots_in_epoch(epoch),
+                absolute_slot: first_slot,
+                block_height: first_slot,
+                transaction_count: None,
+            },
+            epoch_schedule,
+            vote_accounts: RpcVoteAccountStatus {
+                current: vec![],
+                delinquent: vec![],
+            },
+            cluster_nodes: vec![],
+            leader_schedules: HashMap::new(),
+            accounts: HashMap::new(),
+            blocks: BTreeSet::new(),
+            block_transactions: HashMap::new(),
+            inflation_rewards: HashMap::new(),
+            oldest_transactions: HashMap::new(),
+            participants: HashMap::new(),
+        }
+    }
+}
+
+impl ClusterDataSource for InMemoryDataSource {
+    fn get_epoch_info(&self) -> BoxResult<EpochInfo> {
+        Ok(self.epoch_info.clone())
+    }
+
+    fn get_epoch_schedule(&self) -> BoxResult<EpochSchedule> {
+        Ok(self.epoch_schedule.clone())
+    }
+
+    fn get_vote_accounts(&self) -> BoxResult<RpcVoteAccountStatus> {
+        Ok(self.vote_accounts.clone())
+    }
+
+    fn get_cluster_nodes(&self) -> BoxResult<Vec<RpcContactInfo>> {
+        Ok(self.cluster_nodes.clone())
+    }
+
+    fn get_leader_schedule(&self, slot: Slot) -> BoxResult<Option<RpcLeaderSchedule>> {
+        Ok(self
+            .leader_schedules
+            .get(&self.epoch_schedule.get_epoch(slot))
+            .cloned())
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> BoxResult<Option<Account>> {
+        Ok(self.accounts.get(pubkey).cloned())
+    }
+
+    fn get_program_accounts(&self, program_id: &Pubkey) -> BoxResult<Vec<(Pubkey, Account)>> {
+        Ok(self
+            .accounts
+            .iter()
+            .filter(|(_, account)| account.owner == *program_id)
+            .map(|(pubkey, account)| (*pubkey, account.clone()))
+            .collect())
+    }
+
+    fn get_blocks(&self, start_slot: Slot, end_slot: Slot) -> BoxResult<Vec<Slot>> {
+        Ok(self.blocks.range(start_slot..=end_slot).cloned().collect())
+    }
+
+    fn get_block_transactions(&self, slot: Slot) -> BoxResult<Vec<Transaction>> {
+        Ok(self
+            .block_transactions
+            .get(&slot)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn get_inflation_rewards(
+        &self,
+        addresses: &[Pubkey],
+        epoch: Epoch,
+    ) -> BoxResult<Vec<Option<RpcInflationReward>>> {
+        let inflation_rewards = self.inflation_rewards.get(&epoch);
+        Ok(addresses
+            .iter()
+            .map(|address| inflation_rewards.and_then(|rewards| rewards.get(address).cloned()))
+            .collect())
+    }
+
+    fn get_oldest_transaction(
+        &self,
+        address: &Pubkey,
+        _max_signatures: usize,
+    ) -> BoxResult<Option<Transaction>> {
+        Ok(self.oldest_transactions.get(address).cloned())
+    }
+
+    fn get_participants(
+        &self,
+        state: Option<ParticipantState>,
+    ) -> BoxResult<HashMap<Pubkey, Participant>> {
+        Ok(self
+            .participants
+            .iter()
+            .filter(|(_, participant)| state.map_or(true, |state| participant.state == state))
+            .map(|(address, participant)| (*address, participant.clone()))
+            .collect())
+    }
+}
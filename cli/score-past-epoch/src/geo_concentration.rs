@@ -0,0 +1,200 @@
+use {
+    crate::data_center_info::{DataCenterId, DataCenterInfo},
+    std::collections::HashMap,
+};
+
+/// Largest shares listed by the concentration notes of each dimension
+const TOP_N_NOTE: usize = 3;
+
+/// Shares of the stake of the cluster, in percent, of the ASN, country, city and hosting
+/// provider of a data center
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GeoShares {
+    pub asn: f64,
+    pub country: f64,
+    /// 0 when the location of the data center has no city
+    pub city: f64,
+    pub hosting_provider: f64,
+}
+
+/// Stake concentration of the cluster beyond single data centers, by ASN, country, city and
+/// hosting provider
+#[derive(Debug, Default)]
+pub struct GeoConcentration {
+    by_asn: HashMap<String, f64>,
+    by_country: HashMap<String, f64>,
+    by_city: HashMap<String, f64>,
+    by_hosting_provider: HashMap<String, f64>,
+}
+
+impl GeoConcentration {
+    /// Sums the stake percent of the data centers by dimension. `hosting_providers` names the
+    /// provider of the ASNs, the ASNs without a name are providers on their own.
+    pub fn new(data_centers: &[DataCenterInfo], hosting_providers: &HashMap<u64, String>) -> Self {
+        let mut geo_concentration = Self::default();
+        for data_center in data_centers {
+            let keys = GeoKeys::new(&data_center.id, hosting_providers);
+            *geo_concentration.by_asn.entry(keys.asn).or_default() += data_center.stake_percent;
+            *geo_concentration
+                .by_country
+                .entry(keys.country)
+                .or_default() += data_center.stake_percent;
+            if let Some(city) = keys.city {
+                *geo_concentration.by_city.entry(city).or_default() += data_center.stake_percent;
+            }
+            *geo_concentration
+                .by_hosting_provider
+                .entry(keys.hosting_provider)
+                .or_default() += data_center.stake_percent;
+        }
+        geo_concentration
+    }
+
+    pub fn shares(
+        &self,
+        data_center_id: &DataCenterId,
+        hosting_providers: &HashMap<u64, String>,
+    ) -> GeoShares {
+        let keys = GeoKeys::new(data_center_id, hosting_providers);
+        let share =
+            |shares: &HashMap<String, f64>, key: &str| shares.get(key).cloned().unwrap_or_default();
+        GeoShares {
+            asn: share(&self.by_asn, &keys.asn),
+            country: share(&self.by_country, &keys.country),
+            city: keys
+                .city
+                .map(|city| share(&self.by_city, &city))
+                .unwrap_or_default(),
+            hosting_provider: share(&self.by_hosting_provider, &keys.hosting_provider),
+        }
+    }
+
+    /// Herfindahl index and largest shares of each dimension
+    pub fn notes(&self) -> Vec<String> {
+        [
+            ("ASN", &self.by_asn),
+            ("country", &self.by_country),
+            ("city", &self.by_city),
+            ("hosting provider", &self.by_hosting_provider),
+        ]
+        .iter()
+        .map(|(dimension, shares)| {
+            let mut shares: Vec<_> = shares.iter().collect();
+            shares.sort_by(|a, b| b.1.partial_cmp(a.1).unwrap().then(a.0.cmp(b.0)));
+            let top_shares = shares
+                .iter()
+                .take(TOP_N_NOTE)
+                .map(|(key, share)| format!("{} {:.2}%", key, share))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "Stake concentration by {}: Herfindahl index {:.0} over {}, top {}: {}",
+                dimension,
+                herfindahl_index(shares.iter().map(|(_, share)| **share)),
+                shares.len(),
+                TOP_N_NOTE,
+                top_shares
+            )
+        })
+        .collect()
+    }
+}
+
+/// Sum of the squared shares in percent, from 10000 / n for n equal shares up to 10000 for a
+/// single one
+pub fn herfindahl_index<I: IntoIterator<Item = f64>>(shares: I) -> f64 {
+    shares.into_iter().map(|share| share * share).sum()
+}
+
+struct GeoKeys {
+    asn: String,
+    country: String,
+    city: Option<String>,
+    hosting_provider: String,
+}
+
+impl GeoKeys {
+    /// The location of a data center is `{country}` or `{country}-{city}`
+    fn new(data_center_id: &DataCenterId, hosting_providers: &HashMap<u64, String>) -> Self {
+        let mut location = data_center_id.location.splitn(2, '-');
+        let country = location.next().unwrap_or_default().to_string();
+        let city = location.next().map(|_| data_center_id.location.clone());
+        let hosting_provider = if *data_center_id == DataCenterId::default() {
+            data_center_id.location.clone()
+        } else {
+            hosting_providers
+                .get(&data_center_id.asn)
+                .cloned()
+                .unwrap_or_else(|| format!("AS{}", data_center_id.asn))
+        };
+        Self {
+            asn: data_center_id.asn.to_string(),
+            country,
+            city,
+            hosting_provider,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, std::str::FromStr};
+
+    fn data_center(id: &str, stake_percent: f64) -> DataCenterInfo {
+        DataCenterInfo {
+            stake_percent,
+            ..DataCenterInfo::new(DataCenterId::from_str(id).unwrap())
+        }
+    }
+
+    #[test]
+    fn test_geo_concentration() {
+        let data_centers = vec![
+            data_center("24940-DE-Falkenstein", 30.0),
+            data_center("24940-FI-Helsinki", 10.0),
+            data_center("16509-DE-Frankfurt", 20.0),
+            data_center("14618-US-Ashburn", 15.0),
+            data_center("20473-US", 20.0),
+            data_center("0-Unknown", 5.0),
+        ];
+        let hosting_providers: HashMap<_, _> = [
+            (16509, "Amazon.com, Inc.".to_string()),
+            (14618, "Amazon.com, Inc.".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let geo_concentration = GeoConcentration::new(&data_centers, &hosting_providers);
+
+        let shares = |id: &str| {
+            geo_concentration.shares(&DataCenterId::from_str(id).unwrap(), &hosting_providers)
+        };
+        assert_eq!(
+            shares("24940-DE-Falkenstein"),
+            GeoShares {
+                asn: 40.0,
+                country: 50.0,
+                city: 30.0,
+                hosting_provider: 40.0,
+            }
+        );
+        assert_eq!(
+            shares("14618-US-Ashburn"),
+            GeoShares {
+                asn: 15.0,
+                country: 35.0,
+                city: 15.0,
+                hosting_provider: 35.0,
+            }
+        );
+        assert_eq!(shares("20473-US").city, 0.0);
+        assert_eq!(shares("0-Unknown").hosting_provider, 5.0);
+
+        let notes = geo_concentration.notes();
+        assert_eq!(notes.len(), 4);
+        assert_eq!(
+            notes[1],
+            "Stake concentration by country: Herfindahl index 3850 over 4, top 3: DE 50.00%, \
+             US 35.00%, FI 10.00%"
+        );
+    }
+}
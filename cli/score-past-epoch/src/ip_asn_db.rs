@@ -0,0 +1,174 @@
+use {
+    crate::data_center_info::DataCenterId,
+    serde::Deserialize,
+    std::{
+        collections::HashMap,
+        error,
+        fs::File,
+        io::Read,
+        net::{IpAddr, Ipv4Addr, Ipv6Addr},
+        path::Path,
+    },
+};
+
+type BoxResult<T> = Result<T, Box<dyn error::Error>>;
+
+/// A range of the database, in the layout of the ipinfo.io ASN and country CSV exports. `city`
+/// is an extension narrowing the location of the range.
+#[derive(Debug, Deserialize)]
+struct IpRangeRecord {
+    start_ip: IpAddr,
+    end_ip: IpAddr,
+    /// `AS24940`, empty for the ranges not announced
+    asn: String,
+    /// name of the organization announcing the range, `Hetzner Online GmbH`
+    #[serde(default)]
+    as_name: String,
+    #[serde(default)]
+    country: String,
+    #[serde(default)]
+    city: String,
+}
+
+/// Local IP to ASN and location database, mapping the gossip addresses of the validators to
+/// data centers without querying a third party
+#[derive(Debug, Default)]
+pub struct IpAsnDb {
+    /// Ranges sorted by start address
+    v4_ranges: Vec<(u32, u32, DataCenterId)>,
+    v6_ranges: Vec<(u128, u128, DataCenterId)>,
+    as_names: HashMap<u64, String>,
+}
+
+impl IpAsnDb {
+    pub fn load<P: AsRef<Path>>(path: P) -> BoxResult<Self> {
+        let file = File::open(&path).map_err(|err| {
+            format!(
+                "Unable to open the IP to ASN database {}: {}",
+                path.as_ref().display(),
+                err
+            )
+        })?;
+        Self::from_reader(file)
+    }
+
+    pub fn from_reader<R: Read>(reader: R) -> BoxResult<Self> {
+        let mut ip_asn_db = Self::default();
+        for record in csv::Reader::from_reader(reader).deserialize() {
+            let record: IpRangeRecord = record?;
+            let asn = match record.asn.trim_start_matches("AS").parse::<u64>() {
+                Ok(asn) => asn,
+                Err(_) => continue,
+            };
+            if !record.as_name.is_empty() {
+                ip_asn_db.as_names.insert(asn, record.as_name);
+            }
+            let location = match (record.country.is_empty(), record.city.is_empty()) {
+                (true, _) => "Unknown".to_string(),
+                (false, true) => record.country,
+                (false, false) => format!("{}-{}", record.country, record.city),
+            };
+            let data_center_id = DataCenterId { asn, location };
+            match (record.start_ip, record.end_ip) {
+                (IpAddr::V4(start_ip), IpAddr::V4(end_ip)) => {
+                    ip_asn_db
+                        .v4_ranges
+                        .push((start_ip.into(), end_ip.into(), data_center_id))
+                }
+                (IpAddr::V6(start_ip), IpAddr::V6(end_ip)) => {
+                    ip_asn_db
+                        .v6_ranges
+                        .push((start_ip.into(), end_ip.into(), data_center_id))
+                }
+                (start_ip, end_ip) => {
+                    return Err(format!("Invalid IP range {} - {}", start_ip, end_ip).into())
+                }
+            }
+        }
+        ip_asn_db.v4_ranges.sort_by_key(|range| range.0);
+        ip_asn_db.v6_ranges.sort_by_key(|range| range.0);
+        Ok(ip_asn_db)
+    }
+
+    pub fn len(&self) -> usize {
+        self.v4_ranges.len() + self.v6_ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Name of the organization announcing the ranges of `asn`, its hosting provider
+    pub fn as_name(&self, asn: u64) -> Option<&str> {
+        self.as_names.get(&asn).map(String::as_str)
+    }
+
+    /// Data center of the range containing `ip`
+    pub fn lookup(&self, ip: IpAddr) -> Option<&DataCenterId> {
+        match ip {
+            IpAddr::V4(ip) => lookup_range(&self.v4_ranges, u32::from(ip)),
+            IpAddr::V6(ip) => match ipv4_mapped_address(&ip) {
+                Some(ip) => lookup_range(&self.v4_ranges, u32::from(ip)),
+                None => lookup_range(&self.v6_ranges, u128::from(ip)),
+            },
+        }
+    }
+}
+
+fn lookup_range<T: Ord + Copy>(ranges: &[(T, T, DataCenterId)], ip: T) -> Option<&DataCenterId> {
+    // the last range starting at or before `ip`
+    let index = ranges.partition_point(|range| range.0 <= ip);
+    ranges[..index]
+        .last()
+        .filter(|range| ip <= range.1)
+        .map(|range| &range.2)
+}
+
+/// `a.b.c.d` of the IPv4-mapped IPv6 address `::ffff:a.b.c.d`
+fn ipv4_mapped_address(ip: &Ipv6Addr) -> Option<Ipv4Addr> {
+    match ip.octets() {
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, a, b, c, d] => Some(Ipv4Addr::new(a, b, c, d)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let ip_asn_db = IpAsnDb::from_reader(
+            "start_ip,end_ip,country,country_name,asn,as_name,city\n\
+             5.9.0.0,5.9.255.255,DE,Germany,AS24940,Hetzner Online GmbH,Falkenstein\n\
+             1.0.0.0,1.0.0.255,AU,Australia,AS13335,\"Cloudflare, Inc.\",\n\
+             2.0.0.0,2.0.0.255,,,,,\n\
+             2a01:4f8::,2a01:4f8:ffff:ffff:ffff:ffff:ffff:ffff,DE,Germany,AS24940,Hetzner Online GmbH,\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(ip_asn_db.len(), 3);
+
+        let data_center_id = |ip: &str| {
+            ip_asn_db
+                .lookup(ip.parse().unwrap())
+                .map(|id| id.to_string())
+        };
+        assert_eq!(
+            data_center_id("5.9.10.11"),
+            Some("24940-DE-Falkenstein".to_string())
+        );
+        assert_eq!(
+            data_center_id("::ffff:5.9.10.11"),
+            Some("24940-DE-Falkenstein".to_string())
+        );
+        assert_eq!(data_center_id("1.0.0.1"), Some("13335-AU".to_string()));
+        assert_eq!(data_center_id("2a01:4f8::1"), Some("24940-DE".to_string()));
+        assert_eq!(data_center_id("1.0.1.0"), None);
+        assert_eq!(data_center_id("2.0.0.1"), None);
+        assert_eq!(data_center_id("0.0.0.1"), None);
+
+        assert_eq!(ip_asn_db.as_name(24940), Some("Hetzner Online GmbH"));
+        assert_eq!(ip_asn_db.as_name(16509), None);
+    }
+}
@@ -0,0 +1,151 @@
+use {
+    crate::{
+        classification::*, config::*, data_source::ClusterDataSource, snapshot::ClusterSnapshot,
+    },
+    log::*,
+    solana_sdk::clock::Epoch,
+    std::{error, ops::RangeInclusive},
+};
+
+pub mod classification;
+pub mod commission_history;
+pub mod config;
+pub mod config_file;
+pub mod data_center_info;
+pub mod data_source;
+pub mod geo_concentration;
+pub mod ip_asn_db;
+pub mod operator_groups;
+pub mod participants;
+pub mod report;
+pub mod rpc_client_utils;
+pub mod scoring;
+pub mod snapshot;
+pub mod validators_app;
+pub mod validators_list;
+pub mod vote_latency;
+
+pub type BoxResult<T> = Result<T, Box<dyn error::Error>>;
+
+/// Classifies the validators of the last epoch, or of `config.epoch`, and writes the
+/// `epoch-N.yml` and the validator detail CSV into [Config::cluster_db_path]. The cluster state
+/// is read from `config.snapshot_in` if set, from `data_source` otherwise. Returns the
+/// classified epoch. An existing classification is kept, with `config.rescore` the new one is
+/// saved as its next revision.
+pub fn score_epoch(config: &Config, data_source: &dyn ClusterDataSource) -> BoxResult<Epoch> {
+    let snapshot = match &config.snapshot_in {
+        Some(snapshot_in) => Some(ClusterSnapshot::load(snapshot_in)?),
+        None => None,
+    };
+    // epoch-N.yml classifies epoch N - 1
+    let epoch = match (&snapshot, config.epoch) {
+        (Some(snapshot), _) => snapshot.epoch,
+        (None, Some(scored_epoch)) => scored_epoch + 1,
+        (None, None) => data_source.get_epoch_info()?.epoch,
+    };
+    info!("Epoch: {:?}", epoch);
+    assert!(epoch > 0);
+
+    info!("Data directory: {:?}", config.cluster_db_path());
+
+    let previous_revision = EpochClassification::latest_revision(epoch, &config.cluster_db_path());
+    if let Some(previous_revision) = previous_revision {
+        if !config.rescore {
+            error!("Classification for epoch {} already exists", epoch);
+            return Err("Cannot overwrite the previous classification, use --rescore".into());
+        }
+        info!(
+            "Rescoring epoch {}, revision {} exists",
+            epoch, previous_revision
+        );
+    }
+
+    let snapshot = match snapshot {
+        Some(snapshot) => snapshot,
+        None => ClusterSnapshot::capture(data_source, config, epoch)?,
+    };
+    if let Some(snapshot_out) = &config.snapshot_out {
+        snapshot.save(snapshot_out)?;
+    }
+
+    let epoch_classification = classify(&snapshot, config)?;
+
+    match previous_revision {
+        Some(previous_revision) => {
+            let previous_classification = EpochClassification::load_revision(
+                epoch,
+                previous_revision,
+                &config.cluster_db_path(),
+            )?
+            .into_current();
+            let revision = previous_revision + 1;
+            let diff = ClassificationDiff::new(
+                epoch,
+                (previous_revision, &previous_classification),
+                (revision, &epoch_classification),
+                config,
+            );
+            EpochClassification::new(epoch_classification).save_revision(
+                epoch,
+                revision,
+                &config.cluster_db_path(),
+            )?;
+            info!(
+                "Revision {} of epoch {} saved, {} validators changed",
+                revision,
+                epoch,
+                diff.changes.len()
+            );
+            diff.save(&config.cluster_db_path())?;
+        }
+        None => {
+            EpochClassification::new(epoch_classification)
+                .save(epoch, &config.cluster_db_path())?;
+        }
+    }
+    report::generate_validators_csv(epoch, config)?;
+
+    Ok(epoch)
+}
+
+/// Classifies the epochs of `scored_epochs` without an `epoch-N.yml` in
+/// [Config::cluster_db_path]. An epoch failing to classify does not stop the backfill, the
+/// epochs that could not be reconstructed are returned with the reason.
+pub fn backfill(
+    config: &Config,
+    data_source: &dyn ClusterDataSource,
+    scored_epochs: RangeInclusive<Epoch>,
+) -> BoxResult<Vec<(Epoch, String)>> {
+    let current_epoch = data_source.get_epoch_info()?.epoch;
+    if *scored_epochs.end() >= current_epoch {
+        return Err(format!(
+            "Cannot backfill up to epoch {}, epoch {} is in progress",
+            scored_epochs.end(),
+            current_epoch
+        )
+        .into());
+    }
+    info!("Data directory: {:?}", config.cluster_db_path());
+
+    let mut gaps = vec![];
+    for scored_epoch in scored_epochs {
+        // epoch-N.yml classifies epoch N - 1
+        let epoch = scored_epoch + 1;
+        if EpochClassification::exists(epoch, &config.cluster_db_path()) {
+            info!("Epoch {} already classified", scored_epoch);
+            continue;
+        }
+        info!("Backfilling epoch {}", scored_epoch);
+        let result = ClusterSnapshot::capture(data_source, config, epoch)
+            .and_then(|snapshot| classify(&snapshot, config))
+            .and_then(|epoch_classification| {
+                Ok(EpochClassification::new(epoch_classification)
+                    .save(epoch, &config.cluster_db_path())?)
+            });
+        if let Err(err) = result {
+            warn!("Epoch {} could not be classified: {}", scored_epoch, err);
+            gaps.push((scored_epoch, err.to_string()));
+        }
+    }
+    Ok(gaps)
+}
@@ -0,0 +1,272 @@
+use {
+    crate::data_center_info::DataCenterId,
+    serde::{Deserialize, Serialize},
+    solana_sdk::{
+        program_utils::limited_deserialize, pubkey::Pubkey, system_instruction::SystemInstruction,
+        system_program, transaction::Transaction,
+    },
+    std::{
+        collections::{BTreeSet, HashMap},
+        fmt,
+    },
+};
+
+/// Funders of more identities are exchanges or faucets rather than operators
+pub const MAX_IDENTITIES_BY_FUNDER: usize = 10;
+
+/// Evidence that validators are run by the same operator
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum OperatorLink {
+    /// Authorized withdrawer of a vote account
+    WithdrawAuthority(Pubkey),
+    /// Account that funded the identity in its first transaction
+    IdentityFunder(Pubkey),
+    KeybaseId(String),
+    /// www_url without the scheme, the `www.` prefix and the trailing `/`
+    WwwUrl(String),
+    /// Same validators.app name in the same data center
+    NameInDataCenter(String, String),
+}
+
+impl fmt::Display for OperatorLink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OperatorLink::WithdrawAuthority(pubkey) => write!(f, "withdraw-authority:{}", pubkey),
+            OperatorLink::IdentityFunder(pubkey) => write!(f, "identity-funder:{}", pubkey),
+            OperatorLink::KeybaseId(keybase_id) => write!(f, "keybase-id:{}", keybase_id),
+            OperatorLink::WwwUrl(www_url) => write!(f, "www-url:{}", www_url),
+            OperatorLink::NameInDataCenter(name, data_center) => {
+                write!(f, "name-in-data-center:{}@{}", name, data_center)
+            }
+        }
+    }
+}
+
+/// What links a validator to the other validators of its operator
+#[derive(Debug, Default, Clone)]
+pub struct OperatorInfo {
+    pub identity: Pubkey,
+    /// Authorized withdrawers of all the vote accounts of the identity
+    pub withdraw_authorities: Vec<Pubkey>,
+    pub identity_funder: Option<Pubkey>,
+    pub keybase_id: String,
+    pub www_url: String,
+    pub name: String,
+    pub data_center_id: DataCenterId,
+}
+
+impl OperatorInfo {
+    pub fn links(&self) -> BTreeSet<OperatorLink> {
+        let mut links: BTreeSet<_> = self
+            .withdraw_authorities
+            .iter()
+            .map(|withdraw_authority| OperatorLink::WithdrawAuthority(*withdraw_authority))
+            .collect();
+        if let Some(identity_funder) = self.identity_funder {
+            links.insert(OperatorLink::IdentityFunder(identity_funder));
+        }
+        let keybase_id = self.keybase_id.trim().to_lowercase();
+        if !keybase_id.is_empty() {
+            links.insert(OperatorLink::KeybaseId(keybase_id));
+        }
+        let www_url = normalize_www_url(&self.www_url);
+        if !www_url.is_empty() {
+            links.insert(OperatorLink::WwwUrl(www_url));
+        }
+        let name = self.name.trim().to_lowercase();
+        if !name.is_empty() && self.data_center_id != DataCenterId::default() {
+            links.insert(OperatorLink::NameInDataCenter(
+                name,
+                self.data_center_id.to_string(),
+            ));
+        }
+        links
+    }
+}
+
+fn normalize_www_url(www_url: &str) -> String {
+    let www_url = www_url.trim().to_lowercase();
+    let www_url = www_url
+        .strip_prefix("https://")
+        .or_else(|| www_url.strip_prefix("http://"))
+        .unwrap_or(&www_url);
+    let www_url = www_url.strip_prefix("www.").unwrap_or(www_url);
+    www_url.trim_end_matches('/').to_string()
+}
+
+/// Validators linked to the same operator, directly or through other validators of the group
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OperatorGroup {
+    pub id: u32,
+    pub identities: Vec<Pubkey>,
+    /// The links shared by at least two validators of the group
+    pub evidence: Vec<OperatorLink>,
+}
+
+/// Groups the validators sharing a link, the validators without any shared link are not grouped
+pub fn group_operators(operators: &[OperatorInfo]) -> Vec<OperatorGroup> {
+    let links: Vec<_> = operators.iter().map(OperatorInfo::links).collect();
+
+    let mut validators_by_link: HashMap<&OperatorLink, Vec<usize>> = HashMap::new();
+    for (index, links) in links.iter().enumerate() {
+        for link in links {
+            validators_by_link.entry(link).or_default().push(index);
+        }
+    }
+    validators_by_link.retain(|link, validators| {
+        validators.len() > 1
+            && !(matches!(link, OperatorLink::IdentityFunder(_))
+                && validators.len() > MAX_IDENTITIES_BY_FUNDER)
+    });
+
+    // union-find of the validators sharing a link
+    let mut parents: Vec<usize> = (0..operators.len()).collect();
+    fn root(parents: &mut [usize], mut index: usize) -> usize {
+        while parents[index] != index {
+            parents[index] = parents[parents[index]];
+            index = parents[index];
+        }
+        index
+    }
+    for validators in validators_by_link.values() {
+        let first = root(&mut parents, validators[0]);
+        for validator in &validators[1..] {
+            let other = root(&mut parents, *validator);
+            parents[other] = first;
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for index in 0..operators.len() {
+        let root = root(&mut parents, index);
+        members.entry(root).or_default().push(index);
+    }
+    let mut groups = members
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|members| {
+            let mut identities: Vec<_> = members
+                .iter()
+                .map(|index| operators[*index].identity)
+                .collect();
+            identities.sort_by_key(|identity| identity.to_string());
+            let evidence: BTreeSet<_> = validators_by_link
+                .iter()
+                .filter(|(_, validators)| members.contains(&validators[0]))
+                .map(|(link, _)| (*link).clone())
+                .collect();
+            (identities, evidence.into_iter().collect())
+        })
+        .collect::<Vec<(Vec<Pubkey>, Vec<OperatorLink>)>>();
+    // stable group ids for the same groups
+    groups.sort_by_key(|(identities, _)| identities[0].to_string());
+
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(index, (identities, evidence))| OperatorGroup {
+            id: index as u32 + 1,
+            identities,
+            evidence,
+        })
+        .collect()
+}
+
+/// Account that funded `identity` with `transaction`, its first transaction
+pub fn identity_funder(identity: &Pubkey, transaction: &Transaction) -> Option<Pubkey> {
+    let message = &transaction.message;
+    message
+        .instructions
+        .iter()
+        .filter(|instruction| {
+            message
+                .account_keys
+                .get(instruction.program_id_index as usize)
+                == Some(&system_program::id())
+        })
+        .find_map(|instruction| {
+            let account = |position: usize| {
+                instruction
+                    .accounts
+                    .get(position)
+                    .and_then(|index| message.account_keys.get(*index as usize))
+            };
+            match limited_deserialize(&instruction.data).ok()? {
+                SystemInstruction::Transfer { .. }
+                | SystemInstruction::CreateAccount { .. }
+                | SystemInstruction::CreateAccountWithSeed { .. } => {
+                    if account(1)? == identity {
+                        account(0).cloned()
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            }
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, solana_sdk::system_instruction};
+
+    #[test]
+    fn test_group_operators() {
+        let identities: Vec<_> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let withdraw_authority = Pubkey::new_unique();
+        let data_center_id = DataCenterId {
+            asn: 24940,
+            location: "DE-Falkenstein".to_string(),
+        };
+        let operator = |index: usize| OperatorInfo {
+            identity: identities[index],
+            withdraw_authorities: vec![Pubkey::new_unique()],
+            data_center_id: data_center_id.clone(),
+            name: format!("validator {}", index),
+            ..OperatorInfo::default()
+        };
+        let operators = vec![
+            OperatorInfo {
+                withdraw_authorities: vec![withdraw_authority],
+                ..operator(0)
+            },
+            OperatorInfo {
+                withdraw_authorities: vec![withdraw_authority],
+                www_url: "https://www.example.com/".to_string(),
+                ..operator(1)
+            },
+            OperatorInfo {
+                www_url: "http://example.com".to_string(),
+                ..operator(2)
+            },
+            // same data center, different names
+            operator(3),
+            operator(4),
+        ];
+
+        let groups = group_operators(&operators);
+        assert_eq!(groups.len(), 1);
+        let mut expected_identities = identities[0..3].to_vec();
+        expected_identities.sort_by_key(|identity| identity.to_string());
+        assert_eq!(groups[0].identities, expected_identities);
+        assert_eq!(
+            groups[0].evidence,
+            vec![
+                OperatorLink::WithdrawAuthority(withdraw_authority),
+                OperatorLink::WwwUrl("example.com".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identity_funder() {
+        let identity = Pubkey::new_unique();
+        let funder = Pubkey::new_unique();
+        let transaction = Transaction::new_with_payer(
+            &[system_instruction::transfer(&funder, &identity, 1)],
+            Some(&funder),
+        );
+        assert_eq!(identity_funder(&identity, &transaction), Some(funder));
+        assert_eq!(identity_funder(&funder, &transaction), None);
+    }
+}
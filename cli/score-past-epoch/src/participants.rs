@@ -1,6 +1,4 @@
-use log::*;
-use solana_client::rpc_client::RpcClient;
-use solana_foundation_delegation_program_cli::get_participants_with_state;
+use crate::data_source::ClusterDataSource;
 use solana_foundation_delegation_program_registry::state::{Participant, ParticipantState};
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
@@ -9,12 +7,9 @@ use std::error;
 pub type IdentityToParticipant = HashMap<Pubkey, Pubkey>;
 
 pub fn get_participants_identity_maps(
-    rpc_client: &RpcClient,
+    data_source: &dyn ClusterDataSource,
 ) -> Result<(IdentityToParticipant, IdentityToParticipant), Box<dyn error::Error>> {
-    let participants = get_participants_with_state(rpc_client, Some(ParticipantState::Approved))?;
-
-    info!("{} participants loaded", participants.len());
-    assert!(participants.len() > 450);
+    let participants = data_source.get_participants(Some(ParticipantState::Approved))?;
 
     let (mainnet_identity_to_participant, testnet_identity_to_participant): (
         IdentityToParticipant,
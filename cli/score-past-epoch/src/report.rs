@@ -1,70 +1,336 @@
 use {
-    crate::{classification::*, config::*},
+    crate::{classification::*, config::*, operator_groups::OperatorGroup},
+    cli_common::records::{
+        write_artifact, write_jsonl, write_parquet_with_extra, ArtifactFormat, ArtifactWriter,
+        CommissionFlippingRecord, MultipleVoteAccountsRecord, OperatorGroupRecord,
+        ValidatorDetailExportRecord, ValidatorDetailRecord, VoteLatencyRecord,
+    },
     log::*,
-    solana_sdk::{clock::Epoch, native_token::*},
-    std::error,
-    std::{fs::File, io::Write},
+    serde::Serialize,
+    solana_sdk::{clock::Epoch, native_token::*, pubkey::Pubkey},
+    std::{collections::BTreeMap, error},
 };
 
 type BoxResult<T> = Result<T, Box<dyn error::Error>>;
 
+/// A line of the JSON Lines validator detail, the points of the score components by name
+#[derive(Serialize)]
+struct ValidatorDetailLine {
+    #[serde(flatten)]
+    record: ValidatorDetailExportRecord,
+    score_components: BTreeMap<&'static str, Option<i64>>,
+}
+
 pub fn generate_validators_csv(epoch: Epoch, config: &Config) -> BoxResult<()> {
     let epoch_classification =
         EpochClassification::load(epoch, &config.cluster_db_path())?.into_current();
 
     if let Some(ref validator_classifications) = epoch_classification.validator_classifications {
-        let mut validator_detail_csv = vec![];
-        validator_detail_csv.push("epoch,keybase_id,name,identity,vote_address,score,average_position,commission,active_stake,epoch_credits,data_center_concentration,data_center_asn,data_center_location,can_halt_the_network_group,stake_state,stake_state_reason,www_url,version".into());
-        let mut validator_classifications = validator_classifications.iter().collect::<Vec<_>>();
+        // the score breakdown columns depend on the scoring model
+        let component_names = config.scoring_model.component_names();
+        let mut sorted_classifications = validator_classifications.iter().collect::<Vec<_>>();
         // sort by credits, desc
-        validator_classifications.sort_by(|a, b| {
+        sorted_classifications.sort_by(|a, b| {
             b.1.score_data
                 .as_ref()
                 .unwrap()
                 .epoch_credits
                 .cmp(&a.1.score_data.as_ref().unwrap().epoch_credits)
         });
-        for (identity, classification) in validator_classifications {
-            //epoch,keybase_id,name,identity,vote_address,score,average_position,commission,active_stake,epoch_credits,data_center_concentration,can_halt_the_network_group,stake_state,stake_state_reason,www_url
-            if let Some(score_data) = &classification.score_data {
-                let score = score_data.score(config);
 
-                let csv_line = format!(
-                    r#"{},"{}","{}","{}","{}",{},{},{},{},{},{:.4},"{}","{}",{},"{:?}","{}","{}","{}""#,
+        let mut lines = vec![];
+        for (identity, classification) in sorted_classifications {
+            if let Some(score_data) = &classification.score_data {
+                let score_breakdown = match &classification.score_breakdown {
+                    Some(score_breakdown)
+                        if score_breakdown.model == config.scoring_model.name() =>
+                    {
+                        score_breakdown.clone()
+                    }
+                    _ => score_data.score_breakdown(config),
+                };
+                let info = &score_data.validators_app_info;
+                let record = ValidatorDetailRecord {
                     epoch,
-                    escape_quotes(&score_data.validators_app_info.keybase_id),
-                    escape_quotes(&score_data.validators_app_info.name),
-                    identity.to_string(),
-                    classification.vote_address,
-                    score,
-                    score_data.average_position,
-                    score_data.commission,
-                    lamports_to_sol(score_data.active_stake),
-                    score_data.epoch_credits,
-                    score_data.data_center_concentration,
-                    score_data.data_center_asn,
-                    score_data.data_center_location,
-                    score_data.score_discounts.can_halt_the_network_group,
-                    classification.stake_state,
-                    escape_quotes(&classification.stake_state_reason),
-                    escape_quotes(&score_data.validators_app_info.www_url),
-                    score_data.version,
-                );
-                validator_detail_csv.push(csv_line);
+                    keybase_id: info.keybase_id.clone(),
+                    name: info.name.clone(),
+                    identity: identity.to_string(),
+                    vote_address: classification.vote_address.to_string(),
+                    score: score_breakdown.score,
+                    average_position: score_data.average_position,
+                    commission: score_data.commission,
+                    active_stake: lamports_to_sol(score_data.active_stake),
+                    active_stake_lamports: score_data.active_stake,
+                    epoch_credits: score_data.epoch_credits,
+                    data_center_concentration: score_data.data_center_concentration,
+                    data_center_asn: score_data.data_center_asn,
+                    data_center_location: score_data.data_center_location.clone(),
+                    asn_concentration: score_data.asn_concentration,
+                    country_concentration: score_data.country_concentration,
+                    city_concentration: score_data.city_concentration,
+                    hosting_provider_concentration: score_data.hosting_provider_concentration,
+                    can_halt_the_network_group: score_data
+                        .score_discounts
+                        .can_halt_the_network_group,
+                    stake_state: format!("{:?}", classification.stake_state),
+                    stake_state_reason: classification.stake_state_reason.clone(),
+                    www_url: info.www_url.clone(),
+                    version: score_data.version.clone(),
+                    root_distance_score: info.scores.root_distance_score,
+                    vote_distance_score: info.scores.vote_distance_score,
+                    skipped_slot_score: info.scores.skipped_slot_score,
+                    published_information_score: info.scores.published_information_score,
+                    security_report_score: info.scores.security_report_score,
+                    software_version_score: info.scores.software_version_score,
+                    ping_time: info.scores.ping_time,
+                    score_model: score_breakdown.model.clone(),
+                    score_zeroed_reason: score_breakdown.zeroed_reason.clone().unwrap_or_default(),
+                };
+                let components: Vec<Option<i64>> = component_names
+                    .iter()
+                    .map(|name| score_breakdown.component(name))
+                    .collect();
+                lines.push((record, components));
             }
         }
-        // save {cluster}-validator-detail.csv (repeating the cluster in the name is intentional)
-        let filename = config
-            .cluster_db_path()
-            .join(format!("{}-validator-detail.csv", config.cluster));
+
+        // save {cluster}-validator-detail.{format} (repeating the cluster in the name is intentional)
+        let filename = config.cluster_db_path().join(format!(
+            "{}-validator-detail.{}",
+            config.cluster,
+            config.format.extension()
+        ));
         info!("Writing {}", filename.display());
-        let mut file = File::create(filename)?;
-        file.write_all(&validator_detail_csv.join("\n").into_bytes())?;
+        match config.format {
+            ArtifactFormat::Csv => {
+                let mut writer = ArtifactWriter::<ValidatorDetailRecord>::with_extra_columns(
+                    &filename,
+                    component_names,
+                )?;
+                for (record, components) in &lines {
+                    let components: Vec<i64> = components
+                        .iter()
+                        .map(|points| points.unwrap_or_default())
+                        .collect();
+                    writer.write_with_extra(record, &components)?;
+                }
+                writer.finish()?;
+            }
+            ArtifactFormat::Jsonl => write_jsonl(
+                &filename,
+                lines
+                    .iter()
+                    .map(|(record, components)| ValidatorDetailLine {
+                        record: record.into(),
+                        score_components: component_names
+                            .iter()
+                            .cloned()
+                            .zip(components.iter().cloned())
+                            .collect(),
+                    }),
+            )?,
+            // a column per field of the record, then a column per score component of the model
+            ArtifactFormat::Parquet => {
+                let (records, components): (Vec<ValidatorDetailExportRecord>, Vec<_>) = lines
+                    .iter()
+                    .map(|(record, components)| (record.into(), components.clone()))
+                    .unzip();
+                write_parquet_with_extra(&filename, &records, component_names, &components)?
+            }
+        }
+
+        generate_commission_flipping_csv(epoch, validator_classifications, config)?;
+        generate_vote_latency_csv(epoch, validator_classifications, config)?;
+        generate_multiple_vote_accounts_csv(epoch, validator_classifications, config)?;
+        generate_operator_groups_csv(
+            epoch,
+            &epoch_classification.operator_groups,
+            validator_classifications,
+            config,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Writes `{cluster}-commission-flipping.csv`, the validators whose commission at reward time
+/// was above the one at scoring time, read by `process-scores --commission-flipping-file`
+fn generate_commission_flipping_csv(
+    epoch: Epoch,
+    validator_classifications: &ValidatorClassificationByIdentity,
+    config: &Config,
+) -> BoxResult<()> {
+    let mut records = vec![];
+    for (identity, classification) in validator_classifications {
+        if let Some(score_data) = &classification.score_data {
+            if score_data.is_commission_flipping() {
+                records.push(CommissionFlippingRecord {
+                    epoch,
+                    keybase_id: score_data.validators_app_info.keybase_id.clone(),
+                    name: score_data.validators_app_info.name.clone(),
+                    identity: identity.to_string(),
+                    vote_address: classification.vote_address.to_string(),
+                    commission: score_data.commission,
+                    reward_commission: score_data.reward_commission.unwrap_or_default(),
+                });
+            }
+        }
     }
+    let filename = config
+        .cluster_db_path()
+        .join(format!("{}-commission-flipping.csv", config.cluster));
+    info!(
+        "Writing {}, {} validators",
+        filename.display(),
+        records.len()
+    );
+    write_artifact(filename, &records)?;
+    Ok(())
+}
 
+/// Writes `{cluster}-vote-latency.csv`, the average vote latency of the validators it was
+/// measured for, read by `process-scores --vote-latency-file`
+fn generate_vote_latency_csv(
+    epoch: Epoch,
+    validator_classifications: &ValidatorClassificationByIdentity,
+    config: &Config,
+) -> BoxResult<()> {
+    let mut records = vec![];
+    for (identity, classification) in validator_classifications {
+        if let Some(avg_vote_latency) = classification
+            .score_data
+            .as_ref()
+            .and_then(|score_data| score_data.avg_vote_latency)
+        {
+            records.push(VoteLatencyRecord {
+                epoch,
+                identity: identity.to_string(),
+                vote_address: classification.vote_address.to_string(),
+                avg_vote_latency,
+            });
+        }
+    }
+    let filename = config
+        .cluster_db_path()
+        .join(format!("{}-vote-latency.csv", config.cluster));
+    info!(
+        "Writing {}, {} validators",
+        filename.display(),
+        records.len()
+    );
+    write_artifact(filename, &records)?;
+    Ok(())
+}
+
+/// Writes `{cluster}-multiple-vote-accounts.csv`, a line for each vote account of the
+/// validators with multiple vote accounts. `scored` is the vote account the validator was
+/// scored with, the one that voted most recently.
+fn generate_multiple_vote_accounts_csv(
+    epoch: Epoch,
+    validator_classifications: &ValidatorClassificationByIdentity,
+    config: &Config,
+) -> BoxResult<()> {
+    let mut records = vec![];
+    let mut validators = 0;
+    for (identity, classification) in validator_classifications {
+        if classification.other_vote_accounts.is_empty() {
+            continue;
+        }
+        if let Some(score_data) = &classification.score_data {
+            validators += 1;
+            // the active stake of the score data is the stake of all the vote accounts
+            let other_active_stake: u64 = classification
+                .other_vote_accounts
+                .iter()
+                .map(|vote_account| vote_account.active_stake)
+                .sum();
+            let mut push_record = |vote_address: Pubkey,
+                                   scored: bool,
+                                   active_stake: u64,
+                                   commission: u8,
+                                   epoch_credits: u64| {
+                records.push(MultipleVoteAccountsRecord {
+                    epoch,
+                    keybase_id: score_data.validators_app_info.keybase_id.clone(),
+                    name: score_data.validators_app_info.name.clone(),
+                    identity: identity.to_string(),
+                    vote_address: vote_address.to_string(),
+                    scored,
+                    active_stake: lamports_to_sol(active_stake),
+                    active_stake_lamports: active_stake,
+                    commission,
+                    epoch_credits,
+                })
+            };
+            push_record(
+                classification.vote_address,
+                true,
+                score_data.active_stake.saturating_sub(other_active_stake),
+                score_data.commission,
+                score_data.epoch_credits,
+            );
+            for vote_account in &classification.other_vote_accounts {
+                push_record(
+                    vote_account.vote_address,
+                    false,
+                    vote_account.active_stake,
+                    vote_account.commission,
+                    vote_account.epoch_credits,
+                );
+            }
+        }
+    }
+    let filename = config
+        .cluster_db_path()
+        .join(format!("{}-multiple-vote-accounts.csv", config.cluster));
+    info!("Writing {}, {} validators", filename.display(), validators);
+    write_artifact(filename, &records)?;
     Ok(())
 }
 
-fn escape_quotes(original: &String) -> String {
-    original.replace("\"", "\"\"")
+/// Writes `{cluster}-operator-groups.csv`, the validators run by the same operator with the
+/// links found between them, read by `process-scores --operator-groups-file`
+fn generate_operator_groups_csv(
+    epoch: Epoch,
+    operator_groups: &[OperatorGroup],
+    validator_classifications: &ValidatorClassificationByIdentity,
+    config: &Config,
+) -> BoxResult<()> {
+    let mut records = vec![];
+    for operator_group in operator_groups {
+        let evidence = operator_group
+            .evidence
+            .iter()
+            .map(|link| link.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        for identity in &operator_group.identities {
+            if let Some(classification) = validator_classifications.get(identity) {
+                let validators_app_info = classification
+                    .score_data
+                    .as_ref()
+                    .map(|score_data| score_data.validators_app_info.clone())
+                    .unwrap_or_default();
+                records.push(OperatorGroupRecord {
+                    epoch,
+                    operator_group: operator_group.id,
+                    keybase_id: validators_app_info.keybase_id,
+                    name: validators_app_info.name,
+                    identity: identity.to_string(),
+                    vote_address: classification.vote_address.to_string(),
+                    evidence: evidence.clone(),
+                });
+            }
+        }
+    }
+    let filename = config
+        .cluster_db_path()
+        .join(format!("{}-operator-groups.csv", config.cluster));
+    info!(
+        "Writing {}, {} operator groups",
+        filename.display(),
+        operator_groups.len()
+    );
+    write_artifact(filename, &records)?;
+    Ok(())
 }
@@ -1,83 +1,122 @@
 use {
     log::*,
+    serde::{Deserialize, Serialize},
     solana_client::{
         rpc_client::RpcClient,
         rpc_response::{RpcVoteAccountInfo, RpcVoteAccountStatus},
     },
-    solana_sdk::{clock::Epoch, pubkey::Pubkey},
-    std::{collections::HashMap, error, process, str::FromStr, time::Duration},
+    solana_sdk::{
+        clock::{Epoch, Slot},
+        pubkey::Pubkey,
+    },
+    std::{collections::HashMap, process, str::FromStr, time::Duration},
 };
 
+/// A vote account of a validator
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct VoteAccount {
+    pub vote_address: Pubkey,
+    pub commission: u8,
+    pub active_stake: u64,
+
+    /// Credits earned in the epoch
+    pub epoch_credits: u64,
+    pub last_vote: Slot,
+}
+
+/// The vote accounts of a validator identity. The commission and the credits are the ones of
+/// the vote account that voted most recently, the stake is the stake of all the vote accounts.
 pub struct VoteAccountInfo {
     pub identity: Pubkey,
     pub vote_address: Pubkey,
     pub commission: u8,
+    /// Active stake of all the vote accounts
     pub active_stake: u64,
 
     /// Credits earned in the epoch
     pub epoch_credits: u64,
+
+    /// The vote accounts of the identity besides `vote_address`, by descending stake
+    pub other_vote_accounts: Vec<VoteAccount>,
+}
+
+impl VoteAccount {
+    fn new(vote_account_info: &RpcVoteAccountInfo, epoch: Epoch) -> Self {
+        let epoch_credits = if let Some((_last_epoch, credits, prev_credits)) = vote_account_info
+            .epoch_credits
+            .iter()
+            .find(|ec| ec.0 == epoch)
+        {
+            credits.saturating_sub(*prev_credits)
+        } else {
+            0
+        };
+        Self {
+            vote_address: Pubkey::from_str(&vote_account_info.vote_pubkey).unwrap(),
+            commission: vote_account_info.commission,
+            active_stake: vote_account_info.activated_stake,
+            epoch_credits,
+            last_vote: vote_account_info.last_vote,
+        }
+    }
 }
 
 pub fn get_vote_account_info(
-    rpc_client: &RpcClient,
+    vote_accounts: &RpcVoteAccountStatus,
     epoch: Epoch,
-) -> Result<(Vec<VoteAccountInfo>, u64), Box<dyn error::Error>> {
+) -> (Vec<VoteAccountInfo>, u64) {
     let RpcVoteAccountStatus {
         current,
         delinquent,
-    } = rpc_client.get_vote_accounts()?;
+    } = vote_accounts;
 
-    let mut latest_vote_account_info = HashMap::<String, _>::new();
+    let mut vote_accounts_by_identity = HashMap::<String, Vec<VoteAccount>>::new();
 
     let mut total_active_stake = 0;
-    for vote_account_info in current.into_iter().chain(delinquent.into_iter()) {
+    for vote_account_info in current.iter().chain(delinquent.iter()) {
         total_active_stake += vote_account_info.activated_stake;
 
-        let entry = latest_vote_account_info
+        vote_accounts_by_identity
             .entry(vote_account_info.node_pubkey.clone())
-            .or_insert_with(|| vote_account_info.clone());
-
-        // If the validator has multiple staked vote accounts then select the vote account that
-        // voted most recently
-        if entry.last_vote < vote_account_info.last_vote {
-            *entry = vote_account_info.clone();
-        }
+            .or_default()
+            .push(VoteAccount::new(vote_account_info, epoch));
     }
 
-    Ok((
-        latest_vote_account_info
-            .values()
-            .map(
-                |RpcVoteAccountInfo {
-                     commission,
-                     node_pubkey,
-                     vote_pubkey,
-                     epoch_credits,
-                     activated_stake,
-                     ..
-                 }| {
-                    let epoch_credits = if let Some((_last_epoch, credits, prev_credits)) =
-                        epoch_credits.iter().find(|ec| ec.0 == epoch)
-                    {
-                        credits.saturating_sub(*prev_credits)
-                    } else {
-                        0
-                    };
-                    let identity = Pubkey::from_str(node_pubkey).unwrap();
-                    let vote_address = Pubkey::from_str(vote_pubkey).unwrap();
-
-                    VoteAccountInfo {
-                        identity,
-                        vote_address,
-                        active_stake: *activated_stake,
-                        commission: *commission,
-                        epoch_credits,
-                    }
-                },
-            )
+    (
+        vote_accounts_by_identity
+            .into_iter()
+            .map(|(node_pubkey, mut vote_accounts)| {
+                // If the validator has multiple staked vote accounts then its commission and
+                // credits are the ones of the vote account that voted most recently
+                let active_stake = vote_accounts
+                    .iter()
+                    .map(|vote_account| vote_account.active_stake)
+                    .sum();
+                vote_accounts.sort_by(|a, b| b.active_stake.cmp(&a.active_stake));
+                let last_vote = vote_accounts
+                    .iter()
+                    .map(|vote_account| vote_account.last_vote)
+                    .max()
+                    .unwrap();
+                let latest = vote_accounts.remove(
+                    vote_accounts
+                        .iter()
+                        .position(|vote_account| vote_account.last_vote == last_vote)
+                        .unwrap(),
+                );
+
+                VoteAccountInfo {
+                    identity: Pubkey::from_str(&node_pubkey).unwrap(),
+                    vote_address: latest.vote_address,
+                    commission: latest.commission,
+                    active_stake,
+                    epoch_credits: latest.epoch_credits,
+                    other_vote_accounts: vote_accounts,
+                }
+            })
             .collect(),
         total_active_stake,
-    ))
+    )
 }
 
 pub fn rpc_client_health_check(rpc_client: &RpcClient) -> () {
@@ -116,13 +155,16 @@ pub mod test {
         solana_sdk::{
             borsh::get_packed_len,
             clock::Epoch,
+            native_token::sol_to_lamports,
             program_pack::Pack,
             pubkey::Pubkey,
+            signature::{Keypair, Signer},
             stake::{
                 instruction as stake_instruction,
                 state::{Authorized, Lockup},
             },
             system_instruction,
+            transaction::Transaction,
         },
         solana_vote_program::{vote_instruction, vote_state::VoteInit},
         spl_stake_pool::{
@@ -130,6 +172,7 @@ pub mod test {
             state::{Fee, StakePool, ValidatorList},
         },
         spl_token::state::{Account, Mint},
+        std::thread::sleep,
     };
 
     fn new_spinner_progress_bar() -> ProgressBar {
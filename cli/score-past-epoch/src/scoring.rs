@@ -0,0 +1,512 @@
+use {
+    crate::{classification::ScoreData, config::Config},
+    serde::{Deserialize, Serialize},
+    std::fmt,
+};
+
+pub const DEFAULT_SCORING_MODEL: &str = "default";
+
+/// Named part of a score, negative for discounts
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ScoreComponent {
+    pub name: String,
+    pub points: i64,
+}
+
+/// Score of a validator as computed by a scoring model, component by component.
+/// The points of the components add up to `score`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ScoreBreakdown {
+    pub model: String,
+    /// Why the validator is not eligible for a score, `None` if it is
+    pub zeroed_reason: Option<String>,
+    pub components: Vec<ScoreComponent>,
+    pub score: u64,
+}
+
+impl ScoreBreakdown {
+    pub fn component(&self, name: &str) -> Option<i64> {
+        self.components
+            .iter()
+            .find(|component| component.name == name)
+            .map(|component| component.points)
+    }
+}
+
+pub trait ScoringModel {
+    /// Name used to select the model from the command line
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// Names of the components of every breakdown returned by `score`, in order
+    fn component_names(&self) -> &'static [&'static str];
+
+    fn score(&self, score_data: &ScoreData, config: &Config) -> ScoreBreakdown;
+}
+
+impl fmt::Debug for dyn ScoringModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// All the available scoring models, the first one is the default
+pub fn scoring_models() -> &'static [&'static dyn ScoringModel] {
+    const SCORING_MODELS: &[&dyn ScoringModel] = &[
+        &DefaultModel,
+        &LinearApyModel,
+        &BlockProductionModel,
+        &ValidatorsAppModel,
+    ];
+    SCORING_MODELS
+}
+
+pub fn scoring_model_names() -> Vec<&'static str> {
+    scoring_models().iter().map(|model| model.name()).collect()
+}
+
+pub fn scoring_model(name: &str) -> Option<&'static dyn ScoringModel> {
+    scoring_models()
+        .iter()
+        .copied()
+        .find(|model| model.name() == name)
+}
+
+/// Eligibility rules shared by all the models
+fn ineligibility_reason(score_data: &ScoreData, config: &Config) -> Option<String> {
+    if score_data.score_discounts.can_halt_the_network_group {
+        Some("can halt the network group".into())
+    } else if score_data.active_stake < config.score_min_stake {
+        Some("active stake below the minimum".into())
+    } else if score_data.average_position < config.min_avg_position {
+        // if config.min_avg_position=100 => everybody passes
+        // if config.min_avg_position=50 => only validators above avg pass
+        Some("average position below the minimum".into())
+    } else if score_data.commission > config.score_max_commission {
+        Some("commission above the maximum".into())
+    } else {
+        None
+    }
+}
+
+/// Accumulates saturating additions and subtractions, recording the points actually
+/// applied so the components of the breakdown add up to the score
+struct Tally {
+    model: &'static str,
+    score: u64,
+    components: Vec<ScoreComponent>,
+}
+
+impl Tally {
+    fn new(model: &'static str) -> Self {
+        Self {
+            model,
+            score: 0,
+            components: vec![],
+        }
+    }
+
+    fn add(self, name: &str, points: u64) -> Self {
+        let score = self.score.saturating_add(points);
+        self.apply(name, score)
+    }
+
+    fn sub(self, name: &str, points: u64) -> Self {
+        let score = self.score.saturating_sub(points);
+        self.apply(name, score)
+    }
+
+    fn apply(mut self, name: &str, score: u64) -> Self {
+        self.components.push(ScoreComponent {
+            name: name.into(),
+            points: score as i64 - self.score as i64,
+        });
+        self.score = score;
+        self
+    }
+
+    fn into_breakdown(self) -> ScoreBreakdown {
+        ScoreBreakdown {
+            model: self.model.into(),
+            zeroed_reason: None,
+            components: self.components,
+            score: self.score,
+        }
+    }
+
+    /// Score 0 with all the components present, so breakdowns of a model share their columns
+    fn zeroed(self, component_names: &[&str], reason: String) -> ScoreBreakdown {
+        ScoreBreakdown {
+            model: self.model.into(),
+            zeroed_reason: Some(reason),
+            components: component_names
+                .iter()
+                .map(|name| ScoreComponent {
+                    name: name.to_string(),
+                    points: 0,
+                })
+                .collect(),
+            score: 0,
+        }
+    }
+}
+
+// if data_center_concentration = 25%, lose all score,
+// data_center_concentration = 10%, lose 40% (rounded)
+fn data_center_concentration_discount(score_data: &ScoreData, config: &Config) -> u64 {
+    (score_data.data_center_concentration * config.score_concentration_point_discount as f64) as u64
+}
+
+// discount per percentage point of the ASN, country, city and hosting provider stake shares,
+// on top of the data center one
+fn geo_concentration_discount(score_data: &ScoreData, config: &Config) -> u64 {
+    let discounts = &config.score_geo_concentration_point_discounts;
+    (score_data.asn_concentration * discounts.asn as f64
+        + score_data.country_concentration * discounts.country as f64
+        + score_data.city_concentration * discounts.city as f64
+        + score_data.hosting_provider_concentration * discounts.hosting_provider as f64) as u64
+}
+
+// score discounts according to commission
+// apply commission % as a discount to credits_observed.
+// The rationale es:
+// If you're the top performer validator and get 300K credits, but you have 50% commission,
+// from our user's point of view, it's the same as a 150K credits validator with 0% commission,
+// both represent the same APY for the user.
+// So to treat both the same we apply commission to self.epoch_credits
+fn commission_discount(score_data: &ScoreData) -> u64 {
+    score_data.commission as u64 * score_data.epoch_credits / 100
+}
+
+// give extra score to above average validators in order to increase APY for our users
+fn above_average_bonus(score_data: &ScoreData) -> u64 {
+    if score_data.average_position > 50.0 {
+        let above = score_data.average_position - 50.0;
+        let multiplier = if above * above > 25.0 {
+            25.0
+        } else {
+            above * above
+        };
+        (multiplier * score_data.epoch_credits as f64) as u64
+    } else {
+        0
+    }
+}
+
+/// Epoch credits discounted by commission and data center concentration, also by the
+/// ASN, country, city and hosting provider concentration when configured, with a quadratic
+/// bonus for above average validators capped at 25x the credits
+pub struct DefaultModel;
+
+impl ScoringModel for DefaultModel {
+    fn name(&self) -> &'static str {
+        DEFAULT_SCORING_MODEL
+    }
+
+    fn description(&self) -> &'static str {
+        "credits minus commission and concentration discounts, plus above average bonus"
+    }
+
+    fn component_names(&self) -> &'static [&'static str] {
+        &[
+            "epoch_credits",
+            "commission_discount",
+            "data_center_concentration_discount",
+            "geo_concentration_discount",
+            "above_average_bonus",
+        ]
+    }
+
+    fn score(&self, score_data: &ScoreData, config: &Config) -> ScoreBreakdown {
+        let tally = Tally::new(self.name());
+        if let Some(reason) = ineligibility_reason(score_data, config) {
+            return tally.zeroed(self.component_names(), reason);
+        }
+        tally
+            .add("epoch_credits", score_data.epoch_credits)
+            .sub("commission_discount", commission_discount(score_data))
+            .sub(
+                "data_center_concentration_discount",
+                data_center_concentration_discount(score_data, config),
+            )
+            .sub(
+                "geo_concentration_discount",
+                geo_concentration_discount(score_data, config),
+            )
+            .add("above_average_bonus", above_average_bonus(score_data))
+            .into_breakdown()
+    }
+}
+
+/// Epoch credits net of commission, proportional to the rewards of the stakers (APY
+/// equivalent), discounted by data center concentration and without the above average bonus
+pub struct LinearApyModel;
+
+impl ScoringModel for LinearApyModel {
+    fn name(&self) -> &'static str {
+        "linear-apy"
+    }
+
+    fn description(&self) -> &'static str {
+        "credits net of commission minus concentration discount, no bonus"
+    }
+
+    fn component_names(&self) -> &'static [&'static str] {
+        &[
+            "epoch_credits",
+            "commission_discount",
+            "data_center_concentration_discount",
+            "geo_concentration_discount",
+        ]
+    }
+
+    fn score(&self, score_data: &ScoreData, config: &Config) -> ScoreBreakdown {
+        let tally = Tally::new(self.name());
+        if let Some(reason) = ineligibility_reason(score_data, config) {
+            return tally.zeroed(self.component_names(), reason);
+        }
+        tally
+            .add("epoch_credits", score_data.epoch_credits)
+            .sub("commission_discount", commission_discount(score_data))
+            .sub(
+                "data_center_concentration_discount",
+                data_center_concentration_discount(score_data, config),
+            )
+            .sub(
+                "geo_concentration_discount",
+                geo_concentration_discount(score_data, config),
+            )
+            .into_breakdown()
+    }
+}
+
+/// The default model with the credits also discounted by the skip rate of the validator
+/// in its leader slots, validators without leader slots are not discounted
+pub struct BlockProductionModel;
+
+impl ScoringModel for BlockProductionModel {
+    fn name(&self) -> &'static str {
+        "block-production"
+    }
+
+    fn description(&self) -> &'static str {
+        "default model with the credits discounted by the skip rate"
+    }
+
+    fn component_names(&self) -> &'static [&'static str] {
+        &[
+            "epoch_credits",
+            "skip_rate_discount",
+            "commission_discount",
+            "data_center_concentration_discount",
+            "geo_concentration_discount",
+            "above_average_bonus",
+        ]
+    }
+
+    fn score(&self, score_data: &ScoreData, config: &Config) -> ScoreBreakdown {
+        let tally = Tally::new(self.name());
+        if let Some(reason) = ineligibility_reason(score_data, config) {
+            return tally.zeroed(self.component_names(), reason);
+        }
+        let skip_rate_discount = if score_data.leader_slots > 0 {
+            let skipped = score_data
+                .leader_slots
+                .saturating_sub(score_data.blocks_produced);
+            score_data.epoch_credits * skipped / score_data.leader_slots
+        } else {
+            0
+        };
+        tally
+            .add("epoch_credits", score_data.epoch_credits)
+            .sub("skip_rate_discount", skip_rate_discount)
+            .sub("commission_discount", commission_discount(score_data))
+            .sub(
+                "data_center_concentration_discount",
+                data_center_concentration_discount(score_data, config),
+            )
+            .sub(
+                "geo_concentration_discount",
+                geo_concentration_discount(score_data, config),
+            )
+            .add("above_average_bonus", above_average_bonus(score_data))
+            .into_breakdown()
+    }
+}
+
+/// The default model with a bonus for the validators publishing their information and a
+/// security report on validators.app, `config.validators_app_bonus_pct` of the credits per
+/// point of these validators.app scores
+pub struct ValidatorsAppModel;
+
+impl ScoringModel for ValidatorsAppModel {
+    fn name(&self) -> &'static str {
+        "validators-app"
+    }
+
+    fn description(&self) -> &'static str {
+        "default model plus published information and security report bonuses"
+    }
+
+    fn component_names(&self) -> &'static [&'static str] {
+        &[
+            "epoch_credits",
+            "commission_discount",
+            "data_center_concentration_discount",
+            "geo_concentration_discount",
+            "above_average_bonus",
+            "published_information_bonus",
+            "security_report_bonus",
+        ]
+    }
+
+    fn score(&self, score_data: &ScoreData, config: &Config) -> ScoreBreakdown {
+        let tally = Tally::new(self.name());
+        if let Some(reason) = ineligibility_reason(score_data, config) {
+            return tally.zeroed(self.component_names(), reason);
+        }
+        let scores = &score_data.validators_app_info.scores;
+        // negative or missing scores are no bonus
+        let bonus = |sub_score: Option<i64>| {
+            let points = sub_score.unwrap_or_default().max(0) as f64;
+            (points * config.validators_app_bonus_pct / 100.0 * score_data.epoch_credits as f64)
+                as u64
+        };
+        tally
+            .add("epoch_credits", score_data.epoch_credits)
+            .sub("commission_discount", commission_discount(score_data))
+            .sub(
+                "data_center_concentration_discount",
+                data_center_concentration_discount(score_data, config),
+            )
+            .sub(
+                "geo_concentration_discount",
+                geo_concentration_discount(score_data, config),
+            )
+            .add("above_average_bonus", above_average_bonus(score_data))
+            .add(
+                "published_information_bonus",
+                bonus(scores.published_information_score),
+            )
+            .add("security_report_bonus", bonus(scores.security_report_score))
+            .into_breakdown()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, solana_sdk::native_token::sol_to_lamports};
+
+    fn score_data() -> ScoreData {
+        ScoreData {
+            epoch_credits: 400_000,
+            average_position: 52.0,
+            commission: 5,
+            active_stake: sol_to_lamports(50_000.0),
+            data_center_concentration: 10.0,
+            leader_slots: 100,
+            blocks_produced: 90,
+            ..ScoreData::default()
+        }
+    }
+
+    #[test]
+    fn test_components_add_up_to_the_score() {
+        let config = Config::default_for_test();
+        for model in scoring_models() {
+            let breakdown = model.score(&score_data(), &config);
+            assert_eq!(breakdown.model, model.name());
+            assert_eq!(
+                breakdown
+                    .components
+                    .iter()
+                    .map(|component| component.name.as_str())
+                    .collect::<Vec<_>>(),
+                model.component_names()
+            );
+            assert_eq!(
+                breakdown.components.iter().map(|c| c.points).sum::<i64>(),
+                breakdown.score as i64
+            );
+        }
+    }
+
+    #[test]
+    fn test_scoring_model() {
+        for name in scoring_model_names() {
+            assert_eq!(scoring_model(name).unwrap().name(), name);
+        }
+        assert_eq!(scoring_models()[0].name(), DEFAULT_SCORING_MODEL);
+        assert!(scoring_model("linear_apy").is_none());
+    }
+
+    #[test]
+    fn test_default_model() {
+        let config = Config::default_for_test();
+        let breakdown = DefaultModel.score(&score_data(), &config);
+        // 400_000 - 20_000 (5% commission) - 15_000 (10 * 1_500) + 4 * 400_000
+        assert_eq!(breakdown.score, 1_965_000);
+        assert_eq!(breakdown.component("above_average_bonus"), Some(1_600_000));
+
+        let mut halting = score_data();
+        halting.score_discounts.can_halt_the_network_group = true;
+        let breakdown = DefaultModel.score(&halting, &config);
+        assert_eq!(breakdown.score, 0);
+        assert!(breakdown.zeroed_reason.is_some());
+    }
+
+    #[test]
+    fn test_geo_concentration_discount() {
+        let mut config = Config::default_for_test();
+        let mut score_data = score_data();
+        score_data.asn_concentration = 12.0;
+        score_data.country_concentration = 30.0;
+        score_data.city_concentration = 10.0;
+        score_data.hosting_provider_concentration = 15.0;
+        let breakdown = DefaultModel.score(&score_data, &config);
+        assert_eq!(breakdown.component("geo_concentration_discount"), Some(0));
+
+        config.score_geo_concentration_point_discounts.country = 100;
+        config
+            .score_geo_concentration_point_discounts
+            .hosting_provider = 200;
+        let breakdown = DefaultModel.score(&score_data, &config);
+        // 30 * 100 + 15 * 200
+        assert_eq!(
+            breakdown.component("geo_concentration_discount"),
+            Some(-6_000)
+        );
+        assert_eq!(breakdown.score, 1_959_000);
+    }
+
+    #[test]
+    fn test_block_production_model() {
+        let config = Config::default_for_test();
+        let breakdown = BlockProductionModel.score(&score_data(), &config);
+        assert_eq!(breakdown.component("skip_rate_discount"), Some(-40_000));
+        assert_eq!(breakdown.score, 1_925_000);
+    }
+
+    #[test]
+    fn test_validators_app_model() {
+        let config = Config::default_for_test();
+        let mut score_data = score_data();
+        let breakdown = ValidatorsAppModel.score(&score_data, &config);
+        assert_eq!(breakdown.score, 1_965_000);
+
+        score_data
+            .validators_app_info
+            .scores
+            .published_information_score = Some(2);
+        score_data.validators_app_info.scores.security_report_score = Some(1);
+        let breakdown = ValidatorsAppModel.score(&score_data, &config);
+        // 2 * 5% and 5% of 400_000
+        assert_eq!(
+            breakdown.component("published_information_bonus"),
+            Some(40_000)
+        );
+        assert_eq!(breakdown.component("security_report_bonus"), Some(20_000));
+        assert_eq!(breakdown.score, 2_025_000);
+    }
+}
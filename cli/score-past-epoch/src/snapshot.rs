@@ -0,0 +1,550 @@
+use {
+    crate::{
+        commission_history::{CommissionSample, EpochCommissionSamples},
+        config::*,
+        data_center_info::{self, DataCenterId},
+        data_source::ClusterDataSource,
+        ip_asn_db::IpAsnDb,
+        operator_groups::identity_funder,
+        participants::*,
+        rpc_client_utils::*,
+        validators_app,
+        vote_latency::{sample_vote_latencies, VoteLatency},
+    },
+    log::*,
+    serde::{Deserialize, Serialize},
+    solana_client::rpc_response::{RpcContactInfo, RpcLeaderSchedule, RpcVoteAccountStatus},
+    solana_sdk::{
+        account::from_account,
+        account_utils::StateMut,
+        clock::{Epoch, Slot},
+        epoch_schedule::EpochSchedule,
+        pubkey::Pubkey,
+        slot_history::{self, SlotHistory},
+        stake::{self, state::StakeState},
+        stake_history::StakeHistory,
+        sysvar,
+    },
+    solana_vote_program::vote_state::VoteState,
+    std::{
+        collections::{HashMap, HashSet},
+        error,
+        fs::File,
+        io,
+        path::Path,
+    },
+};
+
+type BoxResult<T> = Result<T, Box<dyn error::Error>>;
+
+/// Everything `classify` reads from the cluster and from validators.app, captured at the
+/// beginning of an epoch to score the previous one
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ClusterSnapshot {
+    pub cluster: String,
+
+    /// Epoch in progress when captured, the scored epoch is the previous one
+    pub epoch: Epoch,
+
+    /// Epoch actually in progress when a past epoch was captured (--epoch), `None` otherwise.
+    /// The stakes, commissions, versions and data centers are the ones of this epoch.
+    #[serde(default)]
+    pub captured_in_epoch: Option<Epoch>,
+
+    pub epoch_schedule: EpochSchedule,
+
+    /// The epoch credits of the scored epoch are restored from the vote accounts when
+    /// captured after the RPC history of the credits
+    pub vote_accounts: RpcVoteAccountStatus,
+
+    pub cluster_nodes: Vec<RpcContactInfo>,
+
+    /// Leader schedule of the scored epoch, slots relative to its first slot
+    pub leader_schedule: RpcLeaderSchedule,
+
+    /// Slots of the scored epoch without a confirmed block
+    pub skipped_slots: Vec<Slot>,
+
+    /// Captured only when the self stake is required (--min-self-stake)
+    pub self_stake_by_vote_account: Option<HashMap<Pubkey, u64>>,
+
+    /// Commission the rewards of the scored epoch were paid with, by vote address. `None`
+    /// when the RPC node did not serve the inflation rewards.
+    #[serde(default)]
+    pub reward_commissions: Option<HashMap<Pubkey, u8>>,
+
+    /// Commission samples taken during the scored epoch, see
+    /// [crate::commission_history::sample_commissions]
+    #[serde(default)]
+    pub commission_samples: Vec<CommissionSample>,
+
+    /// Latency of the votes landed in the blocks sampled from the scored epoch
+    /// (--vote-latency-sample-blocks) by vote address, `None` when not measured
+    #[serde(default)]
+    pub vote_latencies: Option<HashMap<Pubkey, VoteLatency>>,
+
+    /// Authorized withdrawer by vote address, `None` when the vote accounts were not read
+    #[serde(default)]
+    pub vote_withdrawers: Option<HashMap<Pubkey, Pubkey>>,
+
+    /// Account that funded each identity in its first transaction, looked up only with
+    /// --identity-funder-max-signatures. The identities with a longer history are left out.
+    #[serde(default)]
+    pub identity_funders: Option<HashMap<Pubkey, Pubkey>>,
+
+    /// Data centers of the validators located from their gossip address, captured with
+    /// --data-center-provider gossip
+    #[serde(default)]
+    pub gossip_data_centers: Option<HashMap<Pubkey, DataCenterId>>,
+
+    /// Hosting provider of the ASNs of the data centers of the cluster, named from the IP to
+    /// ASN database. `None` without --ip-asn-db.
+    #[serde(default)]
+    pub hosting_providers: Option<HashMap<u64, String>>,
+
+    /// `None` for devnet, which is not tracked by validators.app
+    pub validators_app: Option<validators_app::ValidatorsResponse>,
+
+    pub mainnet_identity_to_participant: IdentityToParticipant,
+    pub testnet_identity_to_participant: IdentityToParticipant,
+}
+
+impl ClusterSnapshot {
+    pub fn capture(
+        data_source: &dyn ClusterDataSource,
+        config: &Config,
+        epoch: Epoch,
+    ) -> BoxResult<Self> {
+        let last_epoch = epoch - 1;
+
+        let current_epoch = data_source.get_epoch_info()?.epoch;
+        if epoch > current_epoch {
+            return Err(format!("Epoch {} has not ended yet", last_epoch).into());
+        }
+        let captured_in_epoch = if epoch < current_epoch {
+            info!(
+                "Capturing past epoch {} in epoch {}",
+                last_epoch, current_epoch
+            );
+            Some(current_epoch)
+        } else {
+            None
+        };
+
+        let (mainnet_identity_to_participant, testnet_identity_to_participant) =
+            get_participants_identity_maps(data_source)?;
+
+        let validators_app_cache = config
+            .validators_app_cache
+            .as_ref()
+            .map(|dir| validators_app::ResponseCache::new(dir, &config.cluster.to_string()));
+        let validators_app = match data_center_info::fetch_validators_app(
+            &config.cluster.to_string(),
+            validators_app_cache.as_ref(),
+            config.validators_app_offline,
+        ) {
+            Ok(validators_app) => validators_app,
+            Err(err) if config.data_center_provider == DataCenterProvider::Gossip => {
+                // only the names and urls of the validators are missing
+                warn!("validators.app not available: {}", err);
+                None
+            }
+            Err(err) => {
+                if config.max_infrastructure_concentration.is_some() {
+                    return Err(err);
+                }
+                panic!("infrastructure concentration calc. failed: {}", err);
+            }
+        };
+
+        let mut vote_accounts = data_source.get_vote_accounts()?;
+        if captured_in_epoch.is_some() {
+            restore_epoch_credits(data_source, &mut vote_accounts, last_epoch)?;
+        }
+
+        // Note: get_self_stake_by_vote_account is expensive because it does a RPC call for each validator
+        // we skip this data gathering if config.min_self_stake_lamports==0
+        let self_stake_by_vote_account = if config.min_self_stake_lamports > 0 {
+            let (vote_account_info, _) = get_vote_account_info(&vote_accounts, last_epoch);
+            Some(get_self_stake_by_vote_account(
+                data_source,
+                epoch,
+                &vote_account_info,
+            )?)
+        } else {
+            None
+        };
+
+        let reward_commissions =
+            match get_reward_commissions(data_source, &vote_accounts, last_epoch) {
+                Ok(reward_commissions) => Some(reward_commissions),
+                Err(err) => {
+                    warn!(
+                        "Inflation rewards of epoch {} not available: {}",
+                        last_epoch, err
+                    );
+                    None
+                }
+            };
+        let commission_samples =
+            EpochCommissionSamples::load(last_epoch, &config.commission_samples_path)?.samples;
+        info!(
+            "{} commission samples taken during epoch {}",
+            commission_samples.len(),
+            last_epoch
+        );
+
+        let vote_withdrawers = match get_vote_withdrawers(data_source, &vote_accounts) {
+            Ok(vote_withdrawers) => Some(vote_withdrawers),
+            Err(err) => {
+                warn!(
+                    "Withdraw authorities of the vote accounts not available: {}",
+                    err
+                );
+                None
+            }
+        };
+        let identity_funders = if config.identity_funder_max_signatures > 0 {
+            Some(get_identity_funders(
+                data_source,
+                &vote_accounts,
+                config.identity_funder_max_signatures,
+            )?)
+        } else {
+            None
+        };
+
+        let cluster_nodes = data_source.get_cluster_nodes()?;
+
+        let ip_asn_db = match &config.ip_asn_db_path {
+            Some(ip_asn_db_path) => {
+                let ip_asn_db = IpAsnDb::load(ip_asn_db_path)?;
+                info!(
+                    "IP to ASN database {}: {} ranges",
+                    ip_asn_db_path.display(),
+                    ip_asn_db.len()
+                );
+                Some(ip_asn_db)
+            }
+            None => None,
+        };
+        let gossip_data_centers = match config.data_center_provider {
+            DataCenterProvider::Gossip => {
+                let ip_asn_db = ip_asn_db
+                    .as_ref()
+                    .ok_or("The gossip data center provider requires an IP to ASN database")?;
+                Some(data_center_info::locate_cluster_nodes(
+                    &cluster_nodes,
+                    ip_asn_db,
+                ))
+            }
+            DataCenterProvider::ValidatorsApp => None,
+        };
+        let hosting_providers = ip_asn_db.as_ref().map(|ip_asn_db| {
+            let asns = match &gossip_data_centers {
+                Some(gossip_data_centers) => gossip_data_centers
+                    .values()
+                    .map(|data_center_id| data_center_id.asn)
+                    .collect::<HashSet<_>>(),
+                None => data_center_info::from_validators_app(validators_app.as_ref())
+                    .info
+                    .iter()
+                    .map(|data_center_info| data_center_info.id.asn)
+                    .collect(),
+            };
+            asns.into_iter()
+                .filter_map(|asn| Some((asn, ip_asn_db.as_name(asn)?.to_string())))
+                .collect()
+        });
+
+        let epoch_schedule = data_source.get_epoch_schedule()?;
+        let first_slot_in_epoch = epoch_schedule.get_first_slot_in_epoch(last_epoch);
+        let last_slot_in_epoch = epoch_schedule.get_last_slot_in_epoch(last_epoch);
+
+        let skipped_slots =
+            get_skipped_slots(data_source, first_slot_in_epoch, last_slot_in_epoch)?;
+
+        let vote_latencies = if config.vote_latency_sample_blocks > 0 {
+            let skipped: HashSet<_> = skipped_slots.iter().collect();
+            let confirmed_blocks: Vec<_> = (first_slot_in_epoch..=last_slot_in_epoch)
+                .filter(|slot| !skipped.contains(slot))
+                .collect();
+            match sample_vote_latencies(
+                data_source,
+                &confirmed_blocks,
+                config.vote_latency_sample_blocks,
+            ) {
+                Ok(vote_latencies) => Some(vote_latencies),
+                Err(err) => {
+                    warn!("Vote latency of epoch {} not measured: {}", last_epoch, err);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let leader_schedule = data_source
+            .get_leader_schedule(first_slot_in_epoch)?
+            .ok_or_else(|| {
+                format!(
+                    "Leader schedule of epoch {} is no longer available",
+                    last_epoch
+                )
+            })?;
+
+        Ok(Self {
+            cluster: config.cluster.to_string(),
+            epoch,
+            captured_in_epoch,
+            epoch_schedule,
+            vote_accounts,
+            cluster_nodes,
+            leader_schedule,
+            skipped_slots,
+            self_stake_by_vote_account,
+            reward_commissions,
+            commission_samples,
+            vote_latencies,
+            vote_withdrawers,
+            identity_funders,
+            gossip_data_centers,
+            hosting_providers,
+            validators_app,
+            mainnet_identity_to_participant,
+            testnet_identity_to_participant,
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        info!("Loading cluster snapshot {}", path.as_ref().display());
+        let file = File::open(path)?;
+        serde_yaml::from_reader(file)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), io::Error> {
+        info!("Saving cluster snapshot {}", path.as_ref().display());
+        let file = File::create(path)?;
+        serde_yaml::to_writer(file, self)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))
+    }
+
+    pub fn first_slot_of_scored_epoch(&self) -> Slot {
+        self.epoch_schedule.get_first_slot_in_epoch(self.epoch - 1)
+    }
+
+    /// Slots of the scored epoch with a confirmed block
+    pub fn confirmed_blocks(&self) -> HashSet<Slot> {
+        let skipped_slots: HashSet<_> = self.skipped_slots.iter().collect();
+        (self.first_slot_of_scored_epoch()
+            ..=self.epoch_schedule.get_last_slot_in_epoch(self.epoch - 1))
+            .filter(|slot| !skipped_slots.contains(slot))
+            .collect()
+    }
+}
+
+fn get_self_stake_by_vote_account(
+    data_source: &dyn ClusterDataSource,
+    epoch: Epoch,
+    vote_account_info: &[VoteAccountInfo],
+) -> BoxResult<HashMap<Pubkey, u64>> {
+    let mut self_stake_by_vote_account = HashMap::new();
+
+    info!("Building list of authorized voters...");
+
+    let mut authorized_withdrawer = HashMap::new();
+    let vote_addresses = vote_account_info.iter().flat_map(|info| {
+        std::iter::once(&info.vote_address).chain(
+            info.other_vote_accounts
+                .iter()
+                .map(|vote_account| &vote_account.vote_address),
+        )
+    });
+    for vote_address in vote_addresses {
+        let vote_account = data_source.get_account(vote_address)?;
+
+        if let Some(vote_state) = vote_account.as_ref().and_then(VoteState::from) {
+            authorized_withdrawer.insert(vote_address, vote_state.authorized_withdrawer);
+        }
+    }
+
+    info!("Fetching stake accounts...");
+    let all_stake_accounts = data_source.get_program_accounts(&stake::program::id())?;
+    info!("{} stake accounts", all_stake_accounts.len());
+
+    let stake_history_account = data_source
+        .get_account(&sysvar::stake_history::id())?
+        .ok_or("Stake history sysvar not found")?;
+
+    let stake_history: StakeHistory =
+        from_account(&stake_history_account).ok_or("Failed to deserialize stake history")?;
+
+    for (_stake_pubkey, stake_account) in all_stake_accounts {
+        if let Ok(StakeState::Stake(meta, stake)) = stake_account.state() {
+            let vote_address = &stake.delegation.voter_pubkey;
+            if let Some(vote_account_authorized_withdrawer) =
+                authorized_withdrawer.get(vote_address)
+            {
+                if *vote_account_authorized_withdrawer == meta.authorized.withdrawer {
+                    let effective_stake = stake
+                        .delegation
+                        .stake_activating_and_deactivating(epoch, Some(&stake_history))
+                        .0;
+                    if effective_stake > 0 {
+                        *self_stake_by_vote_account.entry(*vote_address).or_default() +=
+                            effective_stake;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(self_stake_by_vote_account)
+}
+
+/// Commission of the inflation rewards of `epoch` by vote address, the vote accounts without
+/// rewards are left out
+fn get_reward_commissions(
+    data_source: &dyn ClusterDataSource,
+    vote_accounts: &RpcVoteAccountStatus,
+    epoch: Epoch,
+) -> BoxResult<HashMap<Pubkey, u8>> {
+    let vote_addresses = vote_accounts
+        .current
+        .iter()
+        .chain(vote_accounts.delinquent.iter())
+        .map(|info| info.vote_pubkey.parse::<Pubkey>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let inflation_rewards = data_source.get_inflation_rewards(&vote_addresses, epoch)?;
+    Ok(vote_addresses
+        .into_iter()
+        .zip(inflation_rewards)
+        .filter_map(|(vote_address, inflation_reward)| {
+            inflation_reward
+                .and_then(|inflation_reward| inflation_reward.commission)
+                .map(|commission| (vote_address, commission))
+        })
+        .collect())
+}
+
+/// Authorized withdrawer of the vote accounts of `vote_accounts` by vote address
+fn get_vote_withdrawers(
+    data_source: &dyn ClusterDataSource,
+    vote_accounts: &RpcVoteAccountStatus,
+) -> BoxResult<HashMap<Pubkey, Pubkey>> {
+    let vote_addresses = vote_accounts
+        .current
+        .iter()
+        .chain(vote_accounts.delinquent.iter())
+        .map(|info| info.vote_pubkey.parse::<Pubkey>())
+        .collect::<Result<HashSet<_>, _>>()?;
+    Ok(data_source
+        .get_program_accounts(&solana_vote_program::id())?
+        .into_iter()
+        .filter(|(vote_address, _)| vote_addresses.contains(vote_address))
+        .filter_map(|(vote_address, account)| {
+            VoteState::from(&account)
+                .map(|vote_state| (vote_address, vote_state.authorized_withdrawer))
+        })
+        .collect())
+}
+
+/// Funder of the identities of `vote_accounts` found in their first transaction, the
+/// identities with more than `max_signatures` transactions are left out
+fn get_identity_funders(
+    data_source: &dyn ClusterDataSource,
+    vote_accounts: &RpcVoteAccountStatus,
+    max_signatures: usize,
+) -> BoxResult<HashMap<Pubkey, Pubkey>> {
+    let identities = vote_accounts
+        .current
+        .iter()
+        .chain(vote_accounts.delinquent.iter())
+        .map(|info| info.node_pubkey.parse::<Pubkey>())
+        .collect::<Result<HashSet<_>, _>>()?;
+    info!(
+        "Looking up the funders of {} identities...",
+        identities.len()
+    );
+    let mut identity_funders = HashMap::new();
+    for identity in identities {
+        if let Some(transaction) = data_source.get_oldest_transaction(&identity, max_signatures)? {
+            if let Some(funder) = identity_funder(&identity, &transaction) {
+                identity_funders.insert(identity, funder);
+            }
+        }
+    }
+    info!("{} identity funders found", identity_funders.len());
+    Ok(identity_funders)
+}
+
+/// The RPC only returns the credits of the last few epochs, the vote state keeps a longer
+/// history. Restores the credits of `epoch` from the vote state of the accounts missing them.
+fn restore_epoch_credits(
+    data_source: &dyn ClusterDataSource,
+    vote_accounts: &mut RpcVoteAccountStatus,
+    epoch: Epoch,
+) -> BoxResult<()> {
+    let mut restored = 0;
+    let mut missing = 0;
+    for vote_account_info in vote_accounts
+        .current
+        .iter_mut()
+        .chain(vote_accounts.delinquent.iter_mut())
+        .filter(|info| !info.epoch_credits.iter().any(|ec| ec.0 == epoch))
+    {
+        let vote_address = vote_account_info.vote_pubkey.parse::<Pubkey>()?;
+        let epoch_credits = data_source
+            .get_account(&vote_address)?
+            .as_ref()
+            .and_then(VoteState::from)
+            .map(|vote_state| vote_state.epoch_credits().clone())
+            .unwrap_or_default();
+        if epoch_credits.iter().any(|ec| ec.0 == epoch) {
+            vote_account_info.epoch_credits = epoch_credits;
+            restored += 1;
+        } else {
+            // did not vote in the epoch, or the vote state history rolled over as well
+            missing += 1;
+        }
+    }
+    info!(
+        "Epoch {} credits restored from {} vote states, {} vote accounts without credits",
+        epoch, restored, missing
+    );
+    Ok(())
+}
+
+fn get_skipped_slots(
+    data_source: &dyn ClusterDataSource,
+    start_slot: Slot,
+    end_slot: Slot,
+) -> BoxResult<Vec<Slot>> {
+    info!(
+        "loading slot history. slot range is [{},{}]",
+        start_slot, end_slot
+    );
+    let slot_history_account = data_source
+        .get_account(&sysvar::slot_history::id())?
+        .ok_or("Slot history sysvar not found")?;
+
+    let slot_history: SlotHistory =
+        from_account(&slot_history_account).ok_or("Failed to deserialize slot history")?;
+
+    if start_slot >= slot_history.oldest() && end_slot <= slot_history.newest() {
+        info!("slot range within the SlotHistory sysvar");
+        Ok((start_slot..=end_slot)
+            .filter(|slot| slot_history.check(*slot) != slot_history::Check::Found)
+            .collect())
+    } else {
+        info!("slot range is not within the SlotHistory sysvar, loading the confirmed blocks");
+        let confirmed_blocks: HashSet<_> = data_source
+            .get_blocks(start_slot, end_slot)?
+            .into_iter()
+            .collect();
+        Ok((start_slot..=end_slot)
+            .filter(|slot| !confirmed_blocks.contains(slot))
+            .collect())
+    }
+}
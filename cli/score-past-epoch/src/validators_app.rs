@@ -3,10 +3,16 @@ use {
     serde::{Deserialize, Serialize},
     std::{
         collections::HashMap,
-        time::{Duration, Instant},
+        error,
+        fs::{self, File},
+        path::{Path, PathBuf},
+        thread,
+        time::{Duration, Instant, SystemTime, UNIX_EPOCH},
     },
 };
 
+type BoxResult<T> = Result<T, Box<dyn error::Error>>;
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum ClusterJson {
@@ -40,6 +46,10 @@ pub struct ClientConfig {
     pub cluster: ClusterJson,
     pub api_token: String,
     pub timeout: Duration,
+    /// Retries of the requests failing with a timeout, a connection error or a 5xx status
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every following retry
+    pub retry_delay: Duration,
 }
 
 impl Default for ClientConfig {
@@ -49,6 +59,8 @@ impl Default for ClientConfig {
             cluster: ClusterJson::default(),
             api_token: String::default(),
             timeout: Duration::from_secs(90),
+            max_retries: 3,
+            retry_delay: Duration::from_secs(10),
         }
     }
 }
@@ -108,6 +120,33 @@ pub struct ValidatorsResponseEntry {
     pub www_url: Option<String>,
 }
 
+/// The sub-scores validators.app computes for a validator, `None` when not reported
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ValidatorsAppScores {
+    pub root_distance_score: Option<i64>,
+    pub vote_distance_score: Option<i64>,
+    pub skipped_slot_score: Option<i64>,
+    pub published_information_score: Option<i64>,
+    pub security_report_score: Option<i64>,
+    pub software_version_score: Option<i64>,
+    /// milliseconds
+    pub ping_time: Option<f64>,
+}
+
+impl From<&ValidatorsResponseEntry> for ValidatorsAppScores {
+    fn from(entry: &ValidatorsResponseEntry) -> Self {
+        Self {
+            root_distance_score: entry.root_distance_score,
+            vote_distance_score: entry.vote_distance_score,
+            skipped_slot_score: entry.skipped_slot_score,
+            published_information_score: entry.published_information_score,
+            security_report_score: entry.security_report_score,
+            software_version_score: entry.software_version_score,
+            ping_time: entry.ping_time,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ValidatorsResponse(Vec<ValidatorsResponseEntry>);
 
@@ -141,6 +180,8 @@ pub struct Client {
     base_url: reqwest::Url,
     cluster: ClusterJson,
     api_token: String,
+    max_retries: u32,
+    retry_delay: Duration,
     client: reqwest::blocking::Client,
 }
 
@@ -160,11 +201,15 @@ impl Client {
             cluster,
             api_token,
             timeout,
+            max_retries,
+            retry_delay,
         } = config;
         Self {
             base_url: reqwest::Url::parse(&base_url).unwrap(),
             cluster,
             api_token,
+            max_retries,
+            retry_delay,
             client: reqwest::blocking::Client::builder()
                 .timeout(timeout)
                 .build()
@@ -178,21 +223,38 @@ impl Client {
         query: &HashMap<String, String>,
     ) -> reqwest::Result<reqwest::blocking::Response> {
         let url = self.base_url.join(&endpoint.path(&self.cluster)).unwrap();
-        info!("Requesting {}", url);
-        let start = Instant::now();
-        let request = self
-            .client
-            .get(url)
-            .header(TOKEN_HTTP_HEADER_NAME, &self.api_token)
-            .query(&query)
-            .build()?;
-        let result = self.client.execute(request);
-        info!("Response took {:?}", Instant::now().duration_since(start));
-        match result?.error_for_status() {
-            Ok(ok_result) => Ok(ok_result),
-            Err(failure_result) => {
-                error!("Response is not 200(OK): {:?}", failure_result);
+        let mut retries = 0;
+        loop {
+            info!("Requesting {}", url);
+            let start = Instant::now();
+            let request = self
+                .client
+                .get(url.clone())
+                .header(TOKEN_HTTP_HEADER_NAME, &self.api_token)
+                .query(&query)
+                .build()?;
+            let result = self
+                .client
+                .execute(request)
+                .and_then(|response| response.error_for_status());
+            info!("Response took {:?}", Instant::now().duration_since(start));
+            match result {
+                Ok(ok_result) => return Ok(ok_result),
                 Err(failure_result)
+                    if retries < self.max_retries && is_transient(&failure_result) =>
+                {
+                    let retry_delay = self.retry_delay * 2u32.pow(retries);
+                    warn!(
+                        "Request failed, retrying in {:?}: {:?}",
+                        retry_delay, failure_result
+                    );
+                    thread::sleep(retry_delay);
+                    retries += 1;
+                }
+                Err(failure_result) => {
+                    error!("Response is not 200(OK): {:?}", failure_result);
+                    return Err(failure_result);
+                }
             }
         }
     }
@@ -219,3 +281,206 @@ impl Client {
         response.json::<ValidatorsResponse>()
     }
 }
+
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_timeout()
+        || err.is_connect()
+        || err
+            .status()
+            .map_or(false, |status| status.is_server_error())
+}
+
+/// Responses of validators.app saved on disk by cluster and UTC date, `{cluster}-{date}.json`,
+/// to request the full list at most once a day and to replay it offline
+pub struct ResponseCache {
+    dir: PathBuf,
+    cluster: String,
+}
+
+impl ResponseCache {
+    pub fn new<P: AsRef<Path>>(dir: P, cluster: &str) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            cluster: cluster.to_string(),
+        }
+    }
+
+    fn file_name(&self, date: &str) -> PathBuf {
+        self.dir.join(format!("{}-{}.json", self.cluster, date))
+    }
+
+    fn load_file(file_name: &Path) -> BoxResult<ValidatorsResponse> {
+        info!(
+            "Loading the validators.app response {}",
+            file_name.display()
+        );
+        let file = File::open(file_name)?;
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Response saved today, `None` if not requested yet today
+    pub fn load_today(&self) -> BoxResult<Option<ValidatorsResponse>> {
+        let file_name = self.file_name(&utc_date(SystemTime::now()));
+        if !file_name.exists() {
+            return Ok(None);
+        }
+        Self::load_file(&file_name).map(Some)
+    }
+
+    /// Most recent response of the cluster, `None` if none was saved
+    pub fn load_latest(&self) -> BoxResult<Option<ValidatorsResponse>> {
+        if !self.dir.exists() {
+            return Ok(None);
+        }
+        let prefix = format!("{}-", self.cluster);
+        let mut file_names = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let file_name = entry?.file_name().to_string_lossy().to_string();
+            let date = file_name
+                .strip_prefix(&prefix)
+                .and_then(|date| date.strip_suffix(".json"));
+            // leave out the other files of the directory
+            if date.map_or(false, |date| date.len() == "YYYY-MM-DD".len()) {
+                file_names.push(file_name);
+            }
+        }
+        // the ISO dates sort chronologically
+        match file_names.iter().max() {
+            Some(file_name) => Self::load_file(&self.dir.join(file_name)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save(&self, response: &ValidatorsResponse) -> BoxResult<()> {
+        fs::create_dir_all(&self.dir)?;
+        let file_name = self.file_name(&utc_date(SystemTime::now()));
+        info!("Saving the validators.app response {}", file_name.display());
+        let file = File::create(file_name)?;
+        serde_json::to_writer(file, response)?;
+        Ok(())
+    }
+}
+
+/// `YYYY-MM-DD` of `time` in UTC
+fn utc_date(time: SystemTime) -> String {
+    let days = (time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 86_400) as i64;
+    // civil_from_days of http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+pub mod test {
+    use {
+        super::*,
+        std::{
+            io::{Read, Write},
+            net::TcpListener,
+        },
+    };
+
+    pub const MAINNET_FIXTURE: &str = include_str!("../tests/fixtures/validators-app/mainnet.json");
+
+    /// Local stand-in of validators.app answering the requests with `responses`, status and
+    /// body, in order
+    pub fn stand_in_server(responses: Vec<(u16, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/api/v1/", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = vec![];
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Stand-in\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        base_url
+    }
+
+    pub fn stand_in_client(base_url: String) -> Client {
+        Client::new_with_config(ClientConfig {
+            base_url,
+            api_token: "token".to_string(),
+            retry_delay: Duration::from_millis(10),
+            ..ClientConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_validators_retried_on_server_error() {
+        let client = stand_in_client(stand_in_server(vec![
+            (503, ""),
+            (502, ""),
+            (200, MAINNET_FIXTURE),
+        ]));
+        let validators = client.validators(None, None).unwrap();
+        assert_eq!(validators.as_ref().len(), 3);
+
+        // not retried
+        let client = stand_in_client(stand_in_server(vec![(401, "")]));
+        assert!(client.validators(None, None).is_err());
+    }
+
+    #[test]
+    fn test_response_cache() {
+        let dir =
+            std::env::temp_dir().join(format!("validators-app-cache-test-{}", std::process::id()));
+        let cache = ResponseCache::new(&dir, "mainnet-beta");
+        assert!(cache.load_today().unwrap().is_none());
+        assert!(cache.load_latest().unwrap().is_none());
+
+        // an older response of the cluster and a response of another cluster
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("mainnet-beta-2022-01-01.json"), "[]").unwrap();
+        fs::write(dir.join("testnet-2099-01-01.json"), "[]").unwrap();
+
+        let response: ValidatorsResponse = serde_json::from_str(MAINNET_FIXTURE).unwrap();
+        cache.save(&response).unwrap();
+        assert_eq!(cache.load_today().unwrap().unwrap().as_ref().len(), 3);
+        assert_eq!(cache.load_latest().unwrap().unwrap().as_ref().len(), 3);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_utc_date() {
+        assert_eq!(utc_date(UNIX_EPOCH), "1970-01-01");
+        assert_eq!(
+            utc_date(UNIX_EPOCH + Duration::from_secs(18_993 * 86_400 + 86_399)),
+            "2022-01-01"
+        );
+        assert_eq!(
+            utc_date(UNIX_EPOCH + Duration::from_secs(19_416 * 86_400)),
+            "2023-02-28"
+        );
+        assert_eq!(
+            utc_date(UNIX_EPOCH + Duration::from_secs(19_782 * 86_400)),
+            "2024-02-29"
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use {
+    crate::data_source::ClusterDataSource,
+    log::*,
+    serde::{Deserialize, Serialize},
+    solana_sdk::{
+        clock::Slot, program_utils::limited_deserialize, pubkey::Pubkey, transaction::Transaction,
+    },
+    solana_vote_program::vote_instruction::VoteInstruction,
+    std::{collections::HashMap, error},
+};
+
+type BoxResult<T> = Result<T, Box<dyn error::Error>>;
+
+/// Votes of a vote account landed in the sampled blocks of an epoch. The latency of a vote is
+/// the number of slots from the last slot it adds to the tower to the slot it landed in, 1 for
+/// a validator voting as soon as it can. Validators delaying their votes to only vote on the
+/// fork the cluster already agreed on have a much larger latency.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct VoteLatency {
+    pub votes: u64,
+    pub total_latency: u64,
+}
+
+impl VoteLatency {
+    /// Average latency in slots, `None` without any vote
+    pub fn average(&self) -> Option<f64> {
+        if self.votes == 0 {
+            None
+        } else {
+            Some(self.total_latency as f64 / self.votes as f64)
+        }
+    }
+}
+
+/// Vote address and latency of the votes of `transaction`, landed in `slot`
+pub fn landed_votes(slot: Slot, transaction: &Transaction) -> Vec<(Pubkey, u64)> {
+    let message = &transaction.message;
+    message
+        .instructions
+        .iter()
+        .filter(|instruction| {
+            message
+                .account_keys
+                .get(instruction.program_id_index as usize)
+                == Some(&solana_vote_program::id())
+        })
+        .filter_map(|instruction| {
+            let vote = match limited_deserialize(&instruction.data).ok()? {
+                VoteInstruction::Vote(vote) | VoteInstruction::VoteSwitch(vote, _) => vote,
+                _ => return None,
+            };
+            let vote_address = message
+                .account_keys
+                .get(*instruction.accounts.first()? as usize)?;
+            let voted_slot = vote.slots.iter().max()?;
+            Some((*vote_address, slot.saturating_sub(*voted_slot)))
+        })
+        .collect()
+}
+
+/// Latency of the votes landed in `sample_blocks` blocks evenly spread over `confirmed_blocks`
+pub fn sample_vote_latencies(
+    data_source: &dyn ClusterDataSource,
+    confirmed_blocks: &[Slot],
+    sample_blocks: usize,
+) -> BoxResult<HashMap<Pubkey, VoteLatency>> {
+    let mut vote_latencies: HashMap<Pubkey, VoteLatency> = HashMap::new();
+    if confirmed_blocks.is_empty() || sample_blocks == 0 {
+        return Ok(vote_latencies);
+    }
+    let step = (confirmed_blocks.len() / sample_blocks).max(1);
+    for slot in confirmed_blocks.iter().step_by(step).take(sample_blocks) {
+        for transaction in data_source.get_block_transactions(*slot)? {
+            for (vote_address, latency) in landed_votes(*slot, &transaction) {
+                let vote_latency = vote_latencies.entry(vote_address).or_default();
+                vote_latency.votes += 1;
+                vote_latency.total_latency += latency;
+            }
+        }
+    }
+    info!(
+        "Vote latency of {} vote accounts sampled in {} blocks",
+        vote_latencies.len(),
+        sample_blocks.min(confirmed_blocks.len())
+    );
+    Ok(vote_latencies)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_sdk::hash::Hash,
+        solana_vote_program::{vote_instruction, vote_state::Vote},
+    };
+
+    #[test]
+    fn test_landed_votes() {
+        let vote_address = Pubkey::new_unique();
+        let authorized_voter = Pubkey::new_unique();
+        let transaction = Transaction::new_with_payer(
+            &[vote_instruction::vote(
+                &vote_address,
+                &authorized_voter,
+                Vote::new(vec![97, 98, 100], Hash::default()),
+            )],
+            Some(&authorized_voter),
+        );
+        assert_eq!(landed_votes(101, &transaction), vec![(vote_address, 1)]);
+        assert_eq!(landed_votes(150, &transaction), vec![(vote_address, 50)]);
+
+        let transfer = Transaction::new_with_payer(
+            &[solana_sdk::system_instruction::transfer(
+                &authorized_voter,
+                &vote_address,
+                1,
+            )],
+            Some(&authorized_voter),
+        );
+        assert!(landed_votes(101, &transfer).is_empty());
+    }
+}
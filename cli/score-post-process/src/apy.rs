@@ -0,0 +1,185 @@
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::clock::{DEFAULT_TICKS_PER_SECOND, DEFAULT_TICKS_PER_SLOT};
+use std::{fs::File, path::Path};
+
+/// Slots in a year at the nominal slot duration, as used by the inflation schedule
+fn slots_per_year() -> f64 {
+    365.25 * 24.0 * 60.0 * 60.0 * DEFAULT_TICKS_PER_SECOND as f64 / DEFAULT_TICKS_PER_SLOT as f64
+}
+
+/// APY of the stakers of a vote account
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ValidatorApy {
+    pub vote: String,
+    /// Fraction, 0.07 for 7%
+    pub apy: Option<f64>,
+}
+
+/// The APY of the stakers of each vote account, in the layout of the stakeview.app APY
+/// report so a saved report can replace the estimate
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ApyReport {
+    pub validators: Vec<ValidatorApy>,
+}
+
+impl ApyReport {
+    /// Estimates the APY of the stakers of every vote account from the inflation rewards of
+    /// the last epoch, see [ApyReport::from_epoch_rewards]
+    pub fn estimate(client: &RpcClient) -> anyhow::Result<Self> {
+        let epoch_info = client.get_epoch_info()?;
+        if epoch_info.epoch == 0 {
+            bail!("No epoch completed yet");
+        }
+        let last_epoch = epoch_info.epoch - 1;
+        let vote_accounts = client.get_vote_accounts()?;
+        let inflation_rate = client.get_inflation_rate()?;
+        let total_supply = client.get_supply()?.value.total;
+
+        let validators = vote_accounts
+            .current
+            .iter()
+            .chain(vote_accounts.delinquent.iter())
+            .map(|info| {
+                let credits = info
+                    .epoch_credits
+                    .iter()
+                    .find(|(epoch, _, _)| *epoch == last_epoch)
+                    .map(|(_, credits, prev_credits)| credits.saturating_sub(*prev_credits))
+                    .unwrap_or(0);
+                (
+                    info.vote_pubkey.clone(),
+                    info.commission,
+                    credits,
+                    info.activated_stake,
+                )
+            })
+            .collect();
+        Self::from_epoch_rewards(
+            validators,
+            total_supply,
+            inflation_rate.validator,
+            epoch_info.slots_in_epoch,
+        )
+        .with_context(|| format!("Unable to estimate the APY of epoch {}", last_epoch))
+    }
+
+    /// The validator inflation of an epoch of `slots_in_epoch` slots is shared by the stakes in
+    /// proportion to the vote credits earned by their validator, minus its commission.
+    /// `validators` are (vote address, commission, credits earned in the epoch, stake).
+    pub fn from_epoch_rewards(
+        validators: Vec<(String, u8, u64, u64)>,
+        total_supply: u64,
+        validator_inflation_rate: f64,
+        slots_in_epoch: u64,
+    ) -> anyhow::Result<Self> {
+        let total_points: u128 = validators
+            .iter()
+            .map(|(_, _, credits, stake)| *credits as u128 * *stake as u128)
+            .sum();
+        if total_points == 0 {
+            bail!("No vote credits earned");
+        }
+
+        let epoch_duration_in_years = slots_in_epoch as f64 / slots_per_year();
+        let epoch_rewards =
+            validator_inflation_rate * total_supply as f64 * epoch_duration_in_years;
+        let point_value = epoch_rewards / total_points as f64;
+        let validators = validators
+            .into_iter()
+            .map(|(vote, commission, credits, _)| {
+                let epoch_yield =
+                    credits as f64 * point_value * (100 - commission.min(100)) as f64 / 100.0;
+                ValidatorApy {
+                    vote,
+                    apy: Some((1.0 + epoch_yield).powf(1.0 / epoch_duration_in_years) - 1.0),
+                }
+            })
+            .collect();
+        Ok(Self { validators })
+    }
+
+    /// Reads the APY report saved in `path`
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open the APY file {}", path.display()))?;
+        serde_json::from_reader(file)
+            .with_context(|| format!("Invalid APY file {}", path.display()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let file =
+            File::create(path).with_context(|| format!("Unable to create {}", path.display()))?;
+        serde_json::to_writer(file, self)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const STAKE: u64 = 1_000_000;
+
+    fn apy_of(report: &ApyReport, vote: &str) -> f64 {
+        report
+            .validators
+            .iter()
+            .find(|v| v.vote == vote)
+            .unwrap()
+            .apy
+            .unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_from_epoch_rewards() {
+        let validators = vec![
+            ("no-commission".to_string(), 0, 100, STAKE),
+            ("all-commission".to_string(), 100, 100, STAKE),
+            ("half-commission".to_string(), 50, 100, STAKE),
+            ("no-credits".to_string(), 0, 0, STAKE),
+        ];
+        // an epoch of a year: 25% of the supply shared by the 3 validators earning credits
+        let slots_in_epoch = slots_per_year() as u64;
+        let report =
+            ApyReport::from_epoch_rewards(validators, 3 * STAKE, 0.25, slots_in_epoch).unwrap();
+
+        assert_close(apy_of(&report, "no-commission"), 0.25);
+        assert_close(apy_of(&report, "half-commission"), 0.125);
+        assert_eq!(apy_of(&report, "all-commission"), 0.0);
+        assert_eq!(apy_of(&report, "no-credits"), 0.0);
+    }
+
+    #[test]
+    fn test_from_epoch_rewards_annualizes_the_epoch_yield() {
+        let validators = vec![("vote".to_string(), 0, 100, STAKE)];
+        // an epoch of half a year yields half of the rewards, compounded twice a year
+        let slots_in_epoch = slots_per_year() as u64 / 2;
+        let report =
+            ApyReport::from_epoch_rewards(validators, STAKE, 0.25, slots_in_epoch).unwrap();
+
+        assert_close(apy_of(&report, "vote"), 1.125 * 1.125 - 1.0);
+    }
+
+    #[test]
+    fn test_from_epoch_rewards_without_credits() {
+        let validators = vec![
+            ("no-credits".to_string(), 0, 0, STAKE),
+            ("no-stake".to_string(), 0, 100, 0),
+        ];
+        assert!(ApyReport::from_epoch_rewards(validators, STAKE, 0.25, 432_000).is_err());
+        assert!(ApyReport::from_epoch_rewards(vec![], STAKE, 0.25, 432_000).is_err());
+    }
+}
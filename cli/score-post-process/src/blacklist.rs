@@ -0,0 +1,253 @@
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{clock::Epoch, pubkey::Pubkey};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    path::Path,
+    str::FromStr,
+};
+
+/// Version of the blacklist file format, bumped on every incompatible change
+pub const BLACKLIST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BlacklistCategory {
+    CommissionChanges,
+    VoteLagging,
+    Downtime,
+    SameEntity,
+    Exiting,
+    CompromisedKeys,
+    Other,
+}
+
+/// How the validator is penalized, see `process_scores::is_healthy` for the remove levels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BlacklistSeverity {
+    /// score halved
+    Warn,
+    /// score zeroed
+    Unstake,
+    /// score zeroed and removed from the on-chain list
+    Remove,
+}
+
+impl BlacklistSeverity {
+    pub fn remove_level(&self) -> u8 {
+        match self {
+            BlacklistSeverity::Warn => 1,
+            BlacklistSeverity::Unstake => 2,
+            BlacklistSeverity::Remove => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct BlacklistEntry {
+    pub vote_address: String,
+    pub category: BlacklistCategory,
+    pub severity: BlacklistSeverity,
+    /// Shown to the validator, `default-reason` of the file if not set
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Link to the discussion or the on-chain evidence
+    #[serde(default)]
+    pub evidence: Option<String>,
+    /// Internal context of the decision
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Unknown for the entries older than the blacklist file
+    #[serde(default)]
+    pub added_epoch: Option<Epoch>,
+    /// The entry no longer applies from this epoch on
+    #[serde(default)]
+    pub expiry_epoch: Option<Epoch>,
+}
+
+impl BlacklistEntry {
+    pub fn is_active(&self, epoch: Epoch) -> bool {
+        self.expiry_epoch
+            .map_or(true, |expiry_epoch| epoch < expiry_epoch)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Blacklist {
+    pub version: u32,
+    pub default_reason: String,
+    pub entries: Vec<BlacklistEntry>,
+}
+
+impl Blacklist {
+    /// Loads and validates the YAML blacklist file `path`
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open the blacklist file {}", path.display()))?;
+        let blacklist: Blacklist = serde_yaml::from_reader(file)
+            .with_context(|| format!("Invalid blacklist file {}", path.display()))?;
+        blacklist
+            .validate()
+            .with_context(|| format!("Invalid blacklist file {}", path.display()))?;
+        Ok(blacklist)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.version != BLACKLIST_VERSION {
+            bail!(
+                "version {} found, version {} expected",
+                self.version,
+                BLACKLIST_VERSION
+            );
+        }
+        let mut vote_addresses = HashSet::new();
+        let mut duplicates = vec![];
+        for entry in &self.entries {
+            Pubkey::from_str(&entry.vote_address)
+                .with_context(|| format!("Invalid vote address {}", entry.vote_address))?;
+            if let (Some(added_epoch), Some(expiry_epoch)) = (entry.added_epoch, entry.expiry_epoch)
+            {
+                if expiry_epoch <= added_epoch {
+                    bail!(
+                        "{} expires in epoch {}, before being added in epoch {}",
+                        entry.vote_address,
+                        expiry_epoch,
+                        added_epoch
+                    );
+                }
+            }
+            if !vote_addresses.insert(entry.vote_address.as_str()) {
+                duplicates.push(entry.vote_address.as_str());
+            }
+        }
+        if !duplicates.is_empty() {
+            bail!("duplicate vote addresses {}", duplicates.join(", "));
+        }
+        Ok(())
+    }
+
+    /// Entries applying to `epoch` by vote address
+    pub fn active_entries(&self, epoch: Epoch) -> HashMap<&str, &BlacklistEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_active(epoch))
+            .map(|entry| (entry.vote_address.as_str(), entry))
+            .collect()
+    }
+
+    pub fn reason<'a>(&'a self, entry: &'a BlacklistEntry) -> &'a str {
+        entry.reason.as_deref().unwrap_or(&self.default_reason)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const VOTE_ADDRESS: &str = "rep1xGEJzUiQCQgnYjNn76mFRpiPaZaKRwc13wm8mNr";
+    const OTHER_VOTE_ADDRESS: &str = "GfZybqTfVXiiF7yjwnqfwWKm2iwP96sSbHsGdSpwGucH";
+
+    fn entry(vote_address: &str) -> BlacklistEntry {
+        BlacklistEntry {
+            vote_address: vote_address.into(),
+            category: BlacklistCategory::Other,
+            severity: BlacklistSeverity::Unstake,
+            reason: None,
+            evidence: None,
+            note: None,
+            added_epoch: None,
+            expiry_epoch: None,
+        }
+    }
+
+    fn blacklist(entries: Vec<BlacklistEntry>) -> Blacklist {
+        Blacklist {
+            version: BLACKLIST_VERSION,
+            default_reason: "Blacklisted".into(),
+            entries,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            blacklist(vec![entry(VOTE_ADDRESS), entry(OTHER_VOTE_ADDRESS)])
+                .validate()
+                .is_ok()
+        );
+
+        let mut wrong_version = blacklist(vec![]);
+        wrong_version.version = BLACKLIST_VERSION + 1;
+        assert!(wrong_version.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_duplicates() {
+        let err = blacklist(vec![
+            entry(VOTE_ADDRESS),
+            entry(OTHER_VOTE_ADDRESS),
+            entry(VOTE_ADDRESS),
+        ])
+        .validate()
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("duplicate vote addresses {}", VOTE_ADDRESS)
+        );
+    }
+
+    #[test]
+    fn test_validate_invalid_vote_address() {
+        let err = blacklist(vec![entry("not-a-pubkey")])
+            .validate()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Invalid vote address not-a-pubkey");
+    }
+
+    #[test]
+    fn test_validate_expiry_epoch() {
+        let mut expired_when_added = entry(VOTE_ADDRESS);
+        expired_when_added.added_epoch = Some(300);
+        expired_when_added.expiry_epoch = Some(300);
+        assert!(blacklist(vec![expired_when_added.clone()])
+            .validate()
+            .is_err());
+
+        expired_when_added.expiry_epoch = Some(299);
+        assert!(blacklist(vec![expired_when_added.clone()])
+            .validate()
+            .is_err());
+
+        expired_when_added.expiry_epoch = Some(301);
+        assert!(blacklist(vec![expired_when_added]).validate().is_ok());
+    }
+
+    #[test]
+    fn test_is_active() {
+        let mut expiring = entry(VOTE_ADDRESS);
+        assert!(expiring.is_active(300));
+
+        expiring.expiry_epoch = Some(300);
+        assert!(expiring.is_active(299));
+        assert!(!expiring.is_active(300));
+        assert!(!expiring.is_active(301));
+
+        let blacklist = blacklist(vec![expiring, entry(OTHER_VOTE_ADDRESS)]);
+        let active_entries = blacklist.active_entries(300);
+        assert_eq!(active_entries.len(), 1);
+        assert!(active_entries.contains_key(OTHER_VOTE_ADDRESS));
+        assert_eq!(blacklist.active_entries(299).len(), 2);
+    }
+
+    #[test]
+    fn test_load_shipped_blacklist() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config/blacklist.yml");
+        let blacklist = Blacklist::load(&path).unwrap();
+        assert!(!blacklist.entries.is_empty());
+    }
+}
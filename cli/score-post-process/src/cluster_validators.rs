@@ -0,0 +1,121 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use std::{fs::File, path::Path};
+
+/// Vote account of the cluster, with the fields of the `solana validators --output json`
+/// output used by process-scores
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterValidator {
+    #[serde(default)]
+    pub identity_pubkey: String,
+    pub vote_account_pubkey: String,
+    #[serde(default)]
+    pub commission: u8,
+    #[serde(default)]
+    pub activated_stake: u64,
+    /// Credits earned in the current epoch
+    pub epoch_credits: u64,
+    pub delinquent: bool,
+}
+
+/// The vote accounts of the cluster, in the layout of the `solana validators --output json`
+/// output so a saved output can replace them
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ClusterValidators {
+    pub validators: Vec<ClusterValidator>,
+}
+
+impl ClusterValidators {
+    /// Reads the vote accounts of the cluster, the credits are the ones of the current epoch
+    pub fn fetch(client: &RpcClient) -> anyhow::Result<Self> {
+        let vote_accounts = client.get_vote_accounts()?;
+        let current_epoch = client.get_epoch_info()?.epoch;
+        let validators = vote_accounts
+            .current
+            .iter()
+            .map(|info| (info, false))
+            .chain(vote_accounts.delinquent.iter().map(|info| (info, true)))
+            .map(|(info, delinquent)| ClusterValidator {
+                identity_pubkey: info.node_pubkey.clone(),
+                vote_account_pubkey: info.vote_pubkey.clone(),
+                commission: info.commission,
+                activated_stake: info.activated_stake,
+                epoch_credits: info
+                    .epoch_credits
+                    .iter()
+                    .find(|(epoch, _, _)| *epoch == current_epoch)
+                    .map(|(_, credits, prev_credits)| credits.saturating_sub(*prev_credits))
+                    .unwrap_or(0),
+                delinquent,
+            })
+            .collect();
+        Ok(Self { validators })
+    }
+
+    /// Reads the output of `solana validators --output json` saved in `path`
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open the validators file {}", path.display()))?;
+        serde_json::from_reader(file)
+            .with_context(|| format!("Invalid validators file {}", path.display()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let file =
+            File::create(path).with_context(|| format!("Unable to create {}", path.display()))?;
+        serde_json::to_writer(file, self)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fixture() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/solana-validators.json")
+    }
+
+    #[test]
+    fn test_load_solana_validators_output() {
+        let cluster_validators = ClusterValidators::load(fixture()).unwrap();
+        assert_eq!(cluster_validators.validators.len(), 3);
+        assert_eq!(
+            cluster_validators.validators[0],
+            ClusterValidator {
+                identity_pubkey: "7Np41oeYqPefeNQEHSv1UDhYrehxin3NStELsSKCT4K2".into(),
+                vote_account_pubkey: "rep1xGEJzUiQCQgnYjNn76mFRpiPaZaKRwc13wm8mNr".into(),
+                commission: 7,
+                activated_stake: 1_250_000_000_000_000,
+                epoch_credits: 178_542,
+                delinquent: false,
+            }
+        );
+        let delinquent = &cluster_validators.validators[2];
+        assert_eq!(
+            delinquent.vote_account_pubkey,
+            "9GJmEHGom9eWo4np4L5vC6b6ri1Df2xN8KFoWixvD1Bs"
+        );
+        assert_eq!(delinquent.epoch_credits, 0);
+        assert!(delinquent.delinquent);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let cluster_validators = ClusterValidators::load(fixture()).unwrap();
+        let path = std::env::temp_dir().join(format!(
+            "score-post-process-{}-solana-validators.json",
+            std::process::id()
+        ));
+        cluster_validators.save(&path).unwrap();
+        assert_eq!(
+            ClusterValidators::load(&path).unwrap().validators,
+            cluster_validators.validators
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,27 @@
+#![cfg_attr(not(debug_assertions), deny(warnings))]
+
+use cli_common::{ExpandedPath, InputPubkey};
+use structopt::StructOpt;
+
+pub mod apy;
+pub mod blacklist;
+pub mod cluster_validators;
+pub mod policy;
+pub mod process_scores;
+pub mod rebalance;
+
+#[derive(Debug, StructOpt)]
+pub struct Common {
+    #[structopt(short = "c", default_value = "~/.config/solana/cli/config.yml")]
+    pub config_file: ExpandedPath,
+
+    #[structopt(
+        short = "i",
+        env = "MARINADE_INSTANCE",
+        default_value = "auto" //select default instance based on cluster
+        // other possible values:
+        //default_value = "~/.config/mardmin/instance.json"
+        //default_value = "9tA9pzAZWimw2EMZgMjmUwzB2qPKrHhFNaC2ZvCrReeh"
+    )]
+    pub instance: InputPubkey,
+}
@@ -1,7 +1,7 @@
 #![cfg_attr(not(debug_assertions), deny(warnings))]
 
 use anyhow::bail;
-use cli_common::{Cluster, ExpandedPath, InputPubkey};
+use cli_common::{Cluster, InputPubkey};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::commitment_config::CommitmentConfig;
 // use utils::Cluster;
@@ -11,25 +11,9 @@ use log::{debug, error, info};
 use std::{str::FromStr, sync::Arc};
 use structopt::StructOpt;
 
-pub mod process_scores;
-
-use process_scores::ProcessScoresOptions;
-
-#[derive(Debug, StructOpt)]
-pub struct Common {
-    #[structopt(short = "c", default_value = "~/.config/solana/cli/config.yml")]
-    config_file: ExpandedPath,
-
-    #[structopt(
-        short = "i",
-        env = "MARINADE_INSTANCE",
-        default_value = "auto" //select default instance based on cluster
-        // other possible values:
-        //default_value = "~/.config/mardmin/instance.json"
-        //default_value = "9tA9pzAZWimw2EMZgMjmUwzB2qPKrHhFNaC2ZvCrReeh"
-    )]
-    instance: InputPubkey,
-}
+use score_post_process::{
+    process_scores::ProcessScoresOptions, rebalance::PlanRebalanceOptions, Common,
+};
 
 #[derive(Debug, StructOpt)]
 struct Params {
@@ -43,6 +27,7 @@ struct Params {
 #[derive(Debug, StructOpt)]
 enum MardminCommand {
     ProcessScores(ProcessScoresOptions),
+    PlanRebalance(PlanRebalanceOptions),
 }
 
 fn main() -> anyhow::Result<()> {
@@ -86,5 +71,6 @@ fn main() -> anyhow::Result<()> {
 
     Ok(match params.command {
         MardminCommand::ProcessScores(options) => options.process(params.common, client, cluster),
+        MardminCommand::PlanRebalance(options) => options.process(params.common, client, cluster),
     }?)
 }
@@ -0,0 +1,276 @@
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::HashMap, fs::File, path::Path};
+
+/// Version of the policy file format, bumped on every incompatible change
+pub const POLICY_VERSION: u32 = 3;
+
+/// Parameters of the post-processing of the scores into stake targets
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PostProcessPolicy {
+    /// Max percentage of the total score given to one operator: a single validator, or all the
+    /// validators of an operator group together
+    pub pct_cap: f64,
+
+    /// Minimum node version not to be emergency unstaked
+    #[serde(
+        serialize_with = "serialize_release_version",
+        deserialize_with = "deserialize_release_version"
+    )]
+    pub min_release_version: Option<semver::Version>,
+
+    /// How much of total stake is affected by votes, in percent
+    pub vote_gauges_stake_pct: u32,
+
+    /// How many validators are guaranteed to keep their scores
+    pub stake_top_n_validators: usize,
+
+    /// How much of total stake can be given to validators with stake from the
+    /// referral/collateral, in percent
+    pub stake_from_collateral_max_pct: u64,
+
+    /// Stake delta in SOL considered for stake target
+    pub stake_delta: i64,
+
+    /// We allow users to deposit stake accounts from validators with AT MOST this commission
+    pub healthy_validator_max_commission: u8,
+
+    /// Validators below this average position get half of their score
+    pub min_average_position: f64,
+
+    /// Validators with a lower APY, in percent of the average APY of the validators, get half
+    /// of their score
+    pub min_apy_pct_of_average: f64,
+
+    /// Validators whose votes land later on average, in slots after the voted slot, are
+    /// unstaked: they delay their votes to only vote on the fork the cluster agreed on
+    pub max_avg_vote_latency: f64,
+}
+
+impl Default for PostProcessPolicy {
+    fn default() -> Self {
+        Self {
+            pct_cap: 1.5,
+            min_release_version: None,
+            vote_gauges_stake_pct: 20,
+            stake_top_n_validators: 430,
+            stake_from_collateral_max_pct: 30,
+            stake_delta: 100_000,
+            healthy_validator_max_commission: 20,
+            // Solana foundation do not stakes in validators if they're below 40% average
+            min_average_position: 35.0,
+            min_apy_pct_of_average: 90.0,
+            max_avg_vote_latency: 10.0,
+        }
+    }
+}
+
+/// Policy file, a complete policy for each profile:
+///
+/// ```yaml
+/// version: 3
+/// profiles:
+///   mainnet-beta:
+///     pct-cap: 1.5
+///     ...
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PolicyFile {
+    version: u32,
+    profiles: HashMap<String, PostProcessPolicy>,
+}
+
+impl PostProcessPolicy {
+    /// Loads and validates the policy of `profile` from the YAML policy file `path`
+    pub fn load<P: AsRef<Path>>(path: P, profile: &str) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Unable to open the policy file {}", path.display()))?;
+        let mut policy_file: PolicyFile = serde_yaml::from_reader(file)
+            .with_context(|| format!("Invalid policy file {}", path.display()))?;
+        if policy_file.version != POLICY_VERSION {
+            bail!(
+                "Policy file {} has version {}, version {} expected",
+                path.display(),
+                policy_file.version,
+                POLICY_VERSION
+            );
+        }
+        let policy = policy_file.profiles.remove(profile).ok_or_else(|| {
+            anyhow!(
+                "Profile {} not found in the policy file {}",
+                profile,
+                path.display()
+            )
+        })?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(self.pct_cap > 0.0 && self.pct_cap <= 100.0) {
+            bail!("pct-cap must be within ]0, 100], found {}", self.pct_cap);
+        }
+        if self.vote_gauges_stake_pct > 100 {
+            bail!(
+                "vote-gauges-stake-pct must be at most 100, found {}",
+                self.vote_gauges_stake_pct
+            );
+        }
+        if self.stake_from_collateral_max_pct > 100 {
+            bail!(
+                "stake-from-collateral-max-pct must be at most 100, found {}",
+                self.stake_from_collateral_max_pct
+            );
+        }
+        if self.healthy_validator_max_commission > 100 {
+            bail!(
+                "healthy-validator-max-commission must be at most 100, found {}",
+                self.healthy_validator_max_commission
+            );
+        }
+        if !(0.0..=100.0).contains(&self.min_average_position) {
+            bail!(
+                "min-average-position must be within [0, 100], found {}",
+                self.min_average_position
+            );
+        }
+        if !(0.0..=100.0).contains(&self.min_apy_pct_of_average) {
+            bail!(
+                "min-apy-pct-of-average must be within [0, 100], found {}",
+                self.min_apy_pct_of_average
+            );
+        }
+        if self.max_avg_vote_latency.is_nan() || self.max_avg_vote_latency <= 0.0 {
+            bail!(
+                "max-avg-vote-latency must be positive, found {}",
+                self.max_avg_vote_latency
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Written next to the post-processed scores to audit which policy produced them
+#[derive(Debug, Serialize)]
+pub struct PostProcessMetadata<'a> {
+    pub epoch: u64,
+    pub policy_version: u32,
+    /// `None` when the policy is made of the defaults and the command line arguments
+    pub policy_file: Option<&'a str>,
+    pub policy_profile: Option<&'a str>,
+    pub policy: &'a PostProcessPolicy,
+    pub blacklist_file: &'a str,
+}
+
+impl PostProcessMetadata<'_> {
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let file = File::create(path.as_ref())
+            .with_context(|| format!("Unable to create {}", path.as_ref().display()))?;
+        serde_yaml::to_writer(file, self)?;
+        Ok(())
+    }
+}
+
+fn serialize_release_version<S: Serializer>(
+    version: &Option<semver::Version>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match version {
+        Some(version) => serializer.serialize_some(&version.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_release_version<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<semver::Version>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|version| semver::Version::parse(&version).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn shipped_policy_file() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config/score-post-process.yml")
+    }
+
+    fn policy_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "score-post-process-{}-{}",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// The mainnet-beta profile of the shipped policy file, with `replace` replaced by `with`
+    fn mainnet_policy_file(name: &str, replace: &str, with: &str) -> std::path::PathBuf {
+        let contents = std::fs::read_to_string(shipped_policy_file()).unwrap();
+        assert!(contents.contains(replace));
+        policy_file(name, &contents.replacen(replace, with, 1))
+    }
+
+    #[test]
+    fn test_load_shipped_policy_file() {
+        for profile in &["mainnet-beta", "testnet", "devnet"] {
+            let policy = PostProcessPolicy::load(shipped_policy_file(), profile).unwrap();
+            assert_eq!(policy.pct_cap, 1.5, "{}", profile);
+        }
+        let policy = PostProcessPolicy::load(shipped_policy_file(), "mainnet-beta").unwrap();
+        assert_eq!(
+            policy.min_release_version,
+            Some(semver::Version::parse("1.13.1").unwrap())
+        );
+        assert!(PostProcessPolicy::load(shipped_policy_file(), "localnet").is_err());
+    }
+
+    #[test]
+    fn test_load_rejects_an_unknown_key() {
+        let path = mainnet_policy_file(
+            "unknown-key.yml",
+            "    pct-cap: 1.5\n",
+            "    pct-cap: 1.5\n    pct-cup: 1.5\n",
+        );
+        let error = PostProcessPolicy::load(&path, "mainnet-beta").unwrap_err();
+        assert!(format!("{:#}", error).contains("pct-cup"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_load_rejects_another_version() {
+        let path = mainnet_policy_file("version.yml", "version: 3", "version: 2");
+        let error = PostProcessPolicy::load(&path, "mainnet-beta").unwrap_err();
+        assert!(format!("{:#}", error).contains("version 2"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(PostProcessPolicy::default().validate().is_ok());
+        for pct_cap in &[0.0, -1.0, 100.5, f64::NAN] {
+            let policy = PostProcessPolicy {
+                pct_cap: *pct_cap,
+                ..PostProcessPolicy::default()
+            };
+            assert!(policy.validate().is_err(), "pct-cap {}", pct_cap);
+        }
+        let policy = PostProcessPolicy {
+            pct_cap: 100.0,
+            vote_gauges_stake_pct: 100,
+            ..PostProcessPolicy::default()
+        };
+        assert!(policy.validate().is_ok());
+        let policy = PostProcessPolicy {
+            vote_gauges_stake_pct: 101,
+            ..PostProcessPolicy::default()
+        };
+        assert!(policy.validate().is_err());
+    }
+}
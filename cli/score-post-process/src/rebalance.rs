@@ -0,0 +1,661 @@
+use crate::Common;
+use cli_common::{
+    records::{
+        export_artifact, read_artifact, ArtifactFormat, PostProcessedRecord,
+        RebalanceOperationRecord,
+    },
+    rpc_marinade::{RpcMarinade, StakeInfo},
+    Cluster,
+};
+use log::{info, warn};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    clock::Epoch,
+    native_token::{lamports_to_sol, sol_to_lamports},
+    pubkey::Pubkey,
+};
+use std::{collections::HashMap, sync::Arc};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+pub struct PlanRebalanceOptions {
+    #[structopt(
+        long = "post-processed-file",
+        help = "CSV file with the post-processed scores from process-scores"
+    )]
+    post_processed_file: String,
+
+    #[structopt(
+        long = "max-movement",
+        help = "SOL staked, and SOL unstaked, at most in the epoch"
+    )]
+    max_movement: f64,
+
+    #[structopt(
+        long = "min-stake-account",
+        help = "Smallest stake account created by a stake or left by a split, in SOL [default: the min stake of the Marinade instance]"
+    )]
+    min_stake_account: Option<f64>,
+
+    #[structopt(long = "result-file", help = "Path to the output file")]
+    result_file: String,
+
+    #[structopt(
+        long = "format",
+        default_value = "csv",
+        possible_values = ArtifactFormat::NAMES,
+        help = "Format of the result file"
+    )]
+    format: ArtifactFormat,
+}
+
+/// Active stake account of the Marinade stake list
+#[derive(Debug, Clone)]
+pub struct MarinadeStake {
+    pub stake_account: Pubkey,
+    pub vote_address: Pubkey,
+    pub lamports: u64,
+    /// activated before the current epoch, it can be merged with the other ones
+    pub fully_active: bool,
+}
+
+/// Limits of the operations of a plan
+#[derive(Debug, Clone)]
+pub struct RebalanceLimits {
+    /// lamports staked, and lamports unstaked, at most
+    pub max_movement: u64,
+    /// smallest stake account created by a stake or left by a split
+    pub min_stake_account: u64,
+    /// stake accounts the stake list can still take, every stake and split takes one
+    pub free_stake_list_slots: u32,
+}
+
+impl PlanRebalanceOptions {
+    pub fn process(
+        self,
+        common: Common,
+        client: Arc<RpcClient>,
+        _cluster: Cluster,
+    ) -> anyhow::Result<()> {
+        let marinade = RpcMarinade::new(client, &common.instance.as_pubkey())?;
+        let epoch = marinade.client.get_epoch_info()?.epoch;
+
+        info!("Post-processed scores file: {}", self.post_processed_file);
+        let validator_scores: Vec<PostProcessedRecord> = read_artifact(&self.post_processed_file)?;
+
+        let (stakes, stake_list_capacity) = marinade.stakes_info()?;
+        let marinade_stakes = active_stakes(&stakes, epoch);
+        let limits = RebalanceLimits {
+            max_movement: sol_to_lamports(self.max_movement),
+            min_stake_account: self
+                .min_stake_account
+                .map_or(marinade.state.stake_system.min_stake, sol_to_lamports),
+            free_stake_list_slots: stake_list_capacity.saturating_sub(stakes.len() as u32),
+        };
+        info!(
+            "Marinade stake list: {} stake accounts of {} max capacity, {} active",
+            stakes.len(),
+            stake_list_capacity,
+            marinade_stakes.len()
+        );
+        info!("Rebalance limits: {:?}", limits);
+
+        let operations = plan_rebalance(epoch, &validator_scores, &marinade_stakes, &limits);
+        info!(
+            "Save {} operations to {} file {}",
+            operations.len(),
+            self.format,
+            self.result_file
+        );
+        export_artifact(&self.result_file, &operations, self.format)
+    }
+}
+
+/// The stake accounts of the stake list delegated and not deactivating
+pub fn active_stakes(stakes: &[StakeInfo], epoch: Epoch) -> Vec<MarinadeStake> {
+    stakes
+        .iter()
+        .filter_map(|stake| {
+            let delegation = stake.stake.delegation()?;
+            if delegation.deactivation_epoch != u64::MAX {
+                return None;
+            }
+            Some(MarinadeStake {
+                stake_account: stake.record.stake_account,
+                vote_address: delegation.voter_pubkey,
+                lamports: stake.record.last_update_delegated_lamports,
+                fully_active: delegation.activation_epoch < epoch,
+            })
+        })
+        .collect()
+}
+
+/// A validator of the plan, with its current and target Marinade stake
+struct ValidatorTarget<'a> {
+    vote_address: String,
+    name: &'a str,
+    remove_level: u8,
+    remove_level_reason: &'a str,
+    staked: u64,
+    should_have: u64,
+}
+
+/// The operations planned so far, with the stake list slots left
+struct Plan {
+    epoch: Epoch,
+    free_stake_list_slots: u32,
+    operations: Vec<RebalanceOperationRecord>,
+}
+
+impl Plan {
+    fn push(
+        &mut self,
+        target: &ValidatorTarget,
+        operation: &str,
+        stake_account: Option<Pubkey>,
+        destination_stake_account: Option<Pubkey>,
+        lamports: u64,
+        reason: String,
+    ) {
+        self.operations.push(RebalanceOperationRecord {
+            epoch: self.epoch,
+            order: self.operations.len() as u32 + 1,
+            operation: operation.to_string(),
+            vote_address: target.vote_address.clone(),
+            name: target.name.to_string(),
+            stake_account: stake_account.map(|key| key.to_string()).unwrap_or_default(),
+            destination_stake_account: destination_stake_account
+                .map(|key| key.to_string())
+                .unwrap_or_default(),
+            lamports,
+            marinade_staked_lamports: target.staked,
+            should_have_lamports: target.should_have,
+            reason,
+        });
+    }
+}
+
+/// The stake target of the post-processed scores, from `should_have` in the files written
+/// before the lamports column
+fn should_have_lamports(validator_score: &PostProcessedRecord) -> u64 {
+    if validator_score.should_have_lamports == 0 {
+        sol_to_lamports(validator_score.should_have)
+    } else {
+        validator_score.should_have_lamports
+    }
+}
+
+/// Plans the operations moving the Marinade stake towards the `should_have` of the
+/// post-processed scores, within the limits:
+/// - the merges of the fully active stake accounts of each validator, freeing stake list slots
+/// - the unstakes, most penalized and then most overstaked validators first, deactivating
+///   whole stake accounts, smallest first, and splitting one for the rest
+/// - the stakes from the reserve, most understaked validators first
+/// The validators staked by Marinade without a post-processed score are fully unstaked.
+pub fn plan_rebalance(
+    epoch: Epoch,
+    validator_scores: &[PostProcessedRecord],
+    stakes: &[MarinadeStake],
+    limits: &RebalanceLimits,
+) -> Vec<RebalanceOperationRecord> {
+    // the stake accounts of each validator, largest first
+    let mut stakes_by_validator: HashMap<String, Vec<MarinadeStake>> = HashMap::new();
+    for stake in stakes {
+        stakes_by_validator
+            .entry(stake.vote_address.to_string())
+            .or_default()
+            .push(stake.clone());
+    }
+    for validator_stakes in stakes_by_validator.values_mut() {
+        validator_stakes.sort_by(|a, b| b.lamports.cmp(&a.lamports));
+    }
+    let staked = |vote_address: &str| -> u64 {
+        stakes_by_validator
+            .get(vote_address)
+            .map_or(0, |validator_stakes| {
+                validator_stakes.iter().map(|stake| stake.lamports).sum()
+            })
+    };
+
+    let mut targets: Vec<ValidatorTarget> = validator_scores
+        .iter()
+        .map(|v| ValidatorTarget {
+            vote_address: v.vote_address.clone(),
+            name: &v.name,
+            remove_level: v.remove_level,
+            remove_level_reason: &v.remove_level_reason,
+            staked: staked(&v.vote_address),
+            should_have: should_have_lamports(v),
+        })
+        .collect();
+    let mut unscored: Vec<&String> = stakes_by_validator
+        .keys()
+        .filter(|vote_address| !targets.iter().any(|t| &t.vote_address == *vote_address))
+        .collect();
+    unscored.sort();
+    for vote_address in unscored {
+        targets.push(ValidatorTarget {
+            vote_address: vote_address.clone(),
+            name: "",
+            remove_level: 3,
+            remove_level_reason: "This validator is not in the post-processed scores.",
+            staked: staked(vote_address),
+            should_have: 0,
+        });
+    }
+
+    let mut plan = Plan {
+        epoch,
+        free_stake_list_slots: limits.free_stake_list_slots,
+        operations: vec![],
+    };
+
+    // merge the fully active stake accounts of each validator into its largest one
+    for target in &targets {
+        let validator_stakes = match stakes_by_validator.get_mut(&target.vote_address) {
+            Some(validator_stakes) => validator_stakes,
+            None => continue,
+        };
+        let (fully_active, activating): (Vec<_>, Vec<_>) = validator_stakes
+            .drain(..)
+            .partition(|stake| stake.fully_active);
+        let mut merged = fully_active.iter();
+        if let Some(destination) = merged.next() {
+            let mut destination = destination.clone();
+            for source in merged {
+                plan.push(
+                    target,
+                    "merge",
+                    Some(source.stake_account),
+                    Some(destination.stake_account),
+                    source.lamports,
+                    "Merge the stake accounts of the validator.".into(),
+                );
+                plan.free_stake_list_slots += 1;
+                destination.lamports += source.lamports;
+            }
+            validator_stakes.push(destination);
+        }
+        validator_stakes.extend(activating);
+        validator_stakes.sort_by(|a, b| b.lamports.cmp(&a.lamports));
+    }
+
+    // unstake the most penalized and then the most overstaked validators first
+    let mut overstaked: Vec<&ValidatorTarget> = targets
+        .iter()
+        .filter(|target| target.staked > target.should_have)
+        .collect();
+    overstaked.sort_by(|a, b| {
+        b.remove_level
+            .cmp(&a.remove_level)
+            .then((b.staked - b.should_have).cmp(&(a.staked - a.should_have)))
+    });
+    let mut unstake_budget = limits.max_movement;
+    for target in overstaked {
+        let mut excess = (target.staked - target.should_have).min(unstake_budget);
+        if excess < limits.min_stake_account {
+            continue;
+        }
+        let reason = if target.remove_level > 0 {
+            target.remove_level_reason.to_string()
+        } else {
+            format!(
+                "Overstaked by {} SOL.",
+                lamports_to_sol(target.staked - target.should_have)
+            )
+        };
+        let validator_stakes = &stakes_by_validator[&target.vote_address];
+        let mut deactivated = vec![false; validator_stakes.len()];
+        // deactivate whole stake accounts, smallest first
+        for (index, stake) in validator_stakes.iter().enumerate().rev() {
+            if stake.lamports <= excess {
+                plan.push(
+                    target,
+                    "unstake",
+                    Some(stake.stake_account),
+                    None,
+                    stake.lamports,
+                    reason.clone(),
+                );
+                deactivated[index] = true;
+                excess -= stake.lamports;
+                unstake_budget -= stake.lamports;
+            }
+        }
+        // split the rest from the largest stake account left, both parts above the minimum
+        if excess < limits.min_stake_account {
+            continue;
+        }
+        let split = validator_stakes
+            .iter()
+            .zip(&deactivated)
+            .find(|(stake, deactivated)| {
+                !**deactivated && stake.lamports >= excess + limits.min_stake_account
+            });
+        match split {
+            Some((stake, _)) if plan.free_stake_list_slots > 0 => {
+                plan.push(
+                    target,
+                    "unstake",
+                    Some(stake.stake_account),
+                    None,
+                    excess,
+                    reason,
+                );
+                plan.free_stake_list_slots -= 1;
+                unstake_budget -= excess;
+            }
+            Some(_) => warn!(
+                "The stake list is full, {} SOL of {} not unstaked",
+                lamports_to_sol(excess),
+                target.vote_address
+            ),
+            None => {}
+        }
+    }
+
+    // stake the most understaked validators first
+    let mut understaked: Vec<&ValidatorTarget> = targets
+        .iter()
+        .filter(|target| target.should_have > target.staked)
+        .collect();
+    understaked.sort_by(|a, b| (b.should_have - b.staked).cmp(&(a.should_have - a.staked)));
+    let mut stake_budget = limits.max_movement;
+    for target in understaked {
+        let lamports = (target.should_have - target.staked).min(stake_budget);
+        if lamports < limits.min_stake_account {
+            continue;
+        }
+        if plan.free_stake_list_slots == 0 {
+            warn!(
+                "The stake list is full, the validators understaked from {} are not staked",
+                target.vote_address
+            );
+            break;
+        }
+        plan.push(
+            target,
+            "stake",
+            None,
+            None,
+            lamports,
+            format!(
+                "Understaked by {} SOL.",
+                lamports_to_sol(target.should_have - target.staked)
+            ),
+        );
+        plan.free_stake_list_slots -= 1;
+        stake_budget -= lamports;
+    }
+
+    plan.operations
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use solana_sdk::native_token::LAMPORTS_PER_SOL;
+
+    const EPOCH: Epoch = 300;
+
+    fn score(vote_address: &Pubkey, should_have_sol: u64, remove_level: u8) -> PostProcessedRecord {
+        PostProcessedRecord {
+            epoch: EPOCH,
+            name: format!("validator-{}", vote_address),
+            vote_address: vote_address.to_string(),
+            should_have_lamports: should_have_sol * LAMPORTS_PER_SOL,
+            remove_level,
+            remove_level_reason: if remove_level > 0 {
+                "Blacklisted.".into()
+            } else {
+                String::new()
+            },
+            ..PostProcessedRecord::default()
+        }
+    }
+
+    fn stake(vote_address: &Pubkey, sol: u64, fully_active: bool) -> MarinadeStake {
+        MarinadeStake {
+            stake_account: Pubkey::new_unique(),
+            vote_address: *vote_address,
+            lamports: sol * LAMPORTS_PER_SOL,
+            fully_active,
+        }
+    }
+
+    fn limits(
+        max_movement_sol: u64,
+        min_stake_account_sol: u64,
+        free_slots: u32,
+    ) -> RebalanceLimits {
+        RebalanceLimits {
+            max_movement: max_movement_sol * LAMPORTS_PER_SOL,
+            min_stake_account: min_stake_account_sol * LAMPORTS_PER_SOL,
+            free_stake_list_slots: free_slots,
+        }
+    }
+
+    /// (operation, vote address, SOL) of each operation, in order
+    fn summary(operations: &[RebalanceOperationRecord]) -> Vec<(&str, String, u64)> {
+        operations
+            .iter()
+            .map(|op| {
+                (
+                    op.operation.as_str(),
+                    op.vote_address.clone(),
+                    op.lamports / LAMPORTS_PER_SOL,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_frees_a_slot() {
+        let validator = Pubkey::new_unique();
+        let largest = stake(&validator, 10, true);
+        let merged = stake(&validator, 5, true);
+        let activating = stake(&validator, 3, false);
+        let operations = plan_rebalance(
+            EPOCH,
+            &[score(&validator, 20, 0)],
+            &[merged.clone(), activating, largest.clone()],
+            &limits(100, 1, 0),
+        );
+
+        assert_eq!(
+            summary(&operations),
+            vec![
+                ("merge", validator.to_string(), 5),
+                ("stake", validator.to_string(), 2)
+            ]
+        );
+        assert_eq!(
+            operations[0].stake_account,
+            merged.stake_account.to_string()
+        );
+        assert_eq!(
+            operations[0].destination_stake_account,
+            largest.stake_account.to_string()
+        );
+        assert!(operations[1].stake_account.is_empty());
+        assert_eq!(
+            operations.iter().map(|op| op.order).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_unstake_order() {
+        let overstaked = Pubkey::new_unique();
+        let blacklisted = Pubkey::new_unique();
+        let less_overstaked = Pubkey::new_unique();
+        let operations = plan_rebalance(
+            EPOCH,
+            &[
+                score(&overstaked, 10, 0),
+                score(&blacklisted, 0, 2),
+                score(&less_overstaked, 2, 0),
+            ],
+            &[
+                stake(&overstaked, 30, true),
+                stake(&blacklisted, 5, true),
+                stake(&less_overstaked, 12, true),
+            ],
+            &limits(100, 1, 10),
+        );
+
+        assert_eq!(
+            summary(&operations),
+            vec![
+                ("unstake", blacklisted.to_string(), 5),
+                ("unstake", overstaked.to_string(), 20),
+                ("unstake", less_overstaked.to_string(), 10),
+            ]
+        );
+        assert_eq!(operations[0].reason, "Blacklisted.");
+        assert_eq!(operations[1].reason, "Overstaked by 20 SOL.");
+    }
+
+    #[test]
+    fn test_split_keeps_the_min_stake_account() {
+        let below_min = Pubkey::new_unique();
+        let unsplittable = Pubkey::new_unique();
+        let whole_and_split = Pubkey::new_unique();
+        let split = Pubkey::new_unique();
+        let stakes = vec![
+            stake(&below_min, 10, true),
+            stake(&unsplittable, 10, true),
+            stake(&whole_and_split, 3, false),
+            stake(&whole_and_split, 10, false),
+            stake(&split, 10, true),
+        ];
+        let limits = limits(100, 2, 10);
+        let operations = plan_rebalance(
+            EPOCH,
+            &[
+                // 1 SOL over, below the min stake account
+                score(&below_min, 9, 0),
+                // 9 SOL over, a split would leave 1 SOL
+                score(&unsplittable, 1, 0),
+                // 8 SOL over, the 3 SOL account and 5 SOL of the 10 SOL one
+                score(&whole_and_split, 5, 0),
+                // 7 SOL over, 3 SOL left
+                score(&split, 3, 0),
+            ],
+            &stakes,
+            &limits,
+        );
+
+        assert_eq!(
+            summary(&operations),
+            vec![
+                ("unstake", whole_and_split.to_string(), 3),
+                ("unstake", whole_and_split.to_string(), 5),
+                ("unstake", split.to_string(), 7),
+            ]
+        );
+        for op in &operations {
+            let account = stakes
+                .iter()
+                .find(|stake| stake.stake_account.to_string() == op.stake_account)
+                .unwrap();
+            assert!(op.lamports >= limits.min_stake_account);
+            assert!(
+                account.lamports == op.lamports
+                    || account.lamports - op.lamports >= limits.min_stake_account
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_movement() {
+        let overstaked = Pubkey::new_unique();
+        let not_unstaked = Pubkey::new_unique();
+        let understaked = Pubkey::new_unique();
+        let partially_staked = Pubkey::new_unique();
+        let not_staked = Pubkey::new_unique();
+        let operations = plan_rebalance(
+            EPOCH,
+            &[
+                score(&overstaked, 0, 0),
+                score(&not_unstaked, 0, 0),
+                score(&understaked, 8, 0),
+                score(&partially_staked, 6, 0),
+                score(&not_staked, 3, 0),
+            ],
+            &[stake(&overstaked, 30, true), stake(&not_unstaked, 20, true)],
+            &limits(10, 1, 10),
+        );
+
+        assert_eq!(
+            summary(&operations),
+            vec![
+                ("unstake", overstaked.to_string(), 10),
+                ("stake", understaked.to_string(), 8),
+                ("stake", partially_staked.to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_full_stake_list() {
+        let overstaked = Pubkey::new_unique();
+        let unstaked = Pubkey::new_unique();
+        let understaked = Pubkey::new_unique();
+        let scores = vec![
+            score(&overstaked, 10, 0),
+            score(&unstaked, 0, 0),
+            score(&understaked, 10, 0),
+        ];
+        let stakes = vec![stake(&overstaked, 30, true), stake(&unstaked, 5, true)];
+
+        // deactivating a whole stake account needs no slot, a split or a stake does
+        let operations = plan_rebalance(EPOCH, &scores, &stakes, &limits(100, 1, 0));
+        assert_eq!(
+            summary(&operations),
+            vec![("unstake", unstaked.to_string(), 5)]
+        );
+
+        let operations = plan_rebalance(EPOCH, &scores, &stakes, &limits(100, 1, 1));
+        assert_eq!(
+            summary(&operations),
+            vec![
+                ("unstake", overstaked.to_string(), 20),
+                ("unstake", unstaked.to_string(), 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unscored_validators_are_unstaked() {
+        let scored = Pubkey::new_unique();
+        let unscored = Pubkey::new_unique();
+        let largest = stake(&unscored, 6, true);
+        let operations = plan_rebalance(
+            EPOCH,
+            &[score(&scored, 10, 0)],
+            &[
+                stake(&scored, 10, true),
+                stake(&unscored, 4, true),
+                largest.clone(),
+            ],
+            &limits(100, 1, 0),
+        );
+
+        assert_eq!(
+            summary(&operations),
+            vec![
+                ("merge", unscored.to_string(), 4),
+                ("unstake", unscored.to_string(), 10)
+            ]
+        );
+        let unstake = &operations[1];
+        assert_eq!(unstake.stake_account, largest.stake_account.to_string());
+        assert_eq!(unstake.marinade_staked_lamports, 10 * LAMPORTS_PER_SOL);
+        assert_eq!(unstake.should_have_lamports, 0);
+        assert_eq!(
+            unstake.reason,
+            "This validator is not in the post-processed scores."
+        );
+    }
+}
@@ -6,17 +6,12 @@ DB_PATH="$SCRIPT_DIR/db"
 
 mkdir -p "$DB_PATH"
 
+# Extra arguments are passed to run-epoch, e.g. `--resume` after a failed run
 docker run \
   --name delegation-strategy \
   --user "$UID" \
   --rm \
   --volume "$DB_PATH:/usr/local/db" \
   --env "VALIDATORS_APP_TOKEN=$VALIDATORS_APP_TOKEN" \
-  delegation-strategy ./scripts/clean-score-mainnet
-
-docker run \
-  --name delegation-strategy \
-  --user "$UID" \
-  --rm \
-  --volume "$DB_PATH:/usr/local/db" \
-  delegation-strategy ./scripts/score-post-process-mainnet
+  delegation-strategy ./target/debug/delegation-strategy \
+  run-epoch --fetch-scores-db $*